@@ -0,0 +1,962 @@
+//! Blocking display drivers
+//!
+//! The drivers in this module have the same API as the async ones, but use the blocking traits
+//! from `embedded-hal` and can be used without an executor.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{self, InputPin, OutputPin};
+use embedded_hal::i2c::{I2c, Operation};
+use embedded_hal::spi::{self, SpiBus, SpiDevice};
+use premium_pixel::{Framebuffer, Rows, Surface};
+
+use crate::executor::block_on;
+use crate::{
+    Controller, I2cInterface, NoPin, Pcd8544Config, Sh1107Config, SpiError, SpiInterface,
+    Ssh1106Config, St7565Config,
+};
+
+/// Blocking transport for sending commands and data to a display controller
+pub trait Interface {
+    /// Error type of the transport
+    type Error;
+    /// Send command bytes
+    fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+    /// Send display data
+    fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<T: Interface + ?Sized> Interface for &mut T {
+    type Error = T::Error;
+    fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).command(bytes)
+    }
+    fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).data(bytes)
+    }
+}
+
+impl<I: I2c> Interface for I2cInterface<I> {
+    type Error = I::Error;
+    fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut operations = [Operation::Write(&[0x00]), Operation::Write(bytes)];
+        self.i2c.transaction(self.address, &mut operations)
+    }
+    fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut operations = [Operation::Write(&[0x40]), Operation::Write(bytes)];
+        self.i2c.transaction(self.address, &mut operations)
+    }
+}
+
+impl<S: SpiDevice, P: OutputPin> Interface for SpiInterface<S, P> {
+    type Error = SpiError<S::Error, P::Error>;
+    fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.dc.set_low().map_err(SpiError::Pin)?;
+        self.spi.write(bytes).map_err(SpiError::Spi)
+    }
+    fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.dc.set_high().map_err(SpiError::Pin)?;
+        self.spi.write(bytes).map_err(SpiError::Spi)
+    }
+}
+
+/// Use a blocking interface or SPI bus where an async one is expected.
+///
+/// The futures of the async drivers complete on the first poll with this interface, so they can be
+/// driven by [`block_on`] and all driver logic is shared.
+#[derive(Debug)]
+struct Blocking<DI>(DI);
+
+impl<DI: Interface> crate::Interface for Blocking<DI> {
+    type Error = DI::Error;
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.command(bytes)
+    }
+    async fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.data(bytes)
+    }
+}
+
+impl<S: spi::ErrorType> spi::ErrorType for Blocking<S> {
+    type Error = S::Error;
+}
+
+impl<S: SpiBus> embedded_hal_async::spi::SpiBus for Blocking<S> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), S::Error> {
+        self.0.read(words)
+    }
+    async fn write(&mut self, words: &[u8]) -> Result<(), S::Error> {
+        self.0.write(words)
+    }
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), S::Error> {
+        self.0.transfer(read, write)
+    }
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), S::Error> {
+        self.0.transfer_in_place(words)
+    }
+    async fn flush(&mut self) -> Result<(), S::Error> {
+        self.0.flush()
+    }
+}
+
+impl<P: digital::ErrorType> digital::ErrorType for Blocking<P> {
+    type Error = P::Error;
+}
+
+/// Waiting polls the pin until it has the expected level.
+impl<P: InputPin> embedded_hal_async::digital::Wait for Blocking<P> {
+    async fn wait_for_high(&mut self) -> Result<(), P::Error> {
+        while !self.0.is_high()? {}
+        Ok(())
+    }
+    async fn wait_for_low(&mut self) -> Result<(), P::Error> {
+        while !self.0.is_low()? {}
+        Ok(())
+    }
+    async fn wait_for_rising_edge(&mut self) -> Result<(), P::Error> {
+        self.wait_for_low().await?;
+        self.wait_for_high().await
+    }
+    async fn wait_for_falling_edge(&mut self) -> Result<(), P::Error> {
+        self.wait_for_high().await?;
+        self.wait_for_low().await
+    }
+    async fn wait_for_any_edge(&mut self) -> Result<(), P::Error> {
+        let high = self.0.is_high()?;
+        while self.0.is_high()? == high {}
+        Ok(())
+    }
+}
+
+impl<D: DelayNs> embedded_hal_async::delay::DelayNs for Blocking<D> {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_ns(ns)
+    }
+    async fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us)
+    }
+    async fn delay_ms(&mut self, ms: u32) {
+        self.0.delay_ms(ms)
+    }
+}
+
+macro_rules! surface {
+    ($driver:ident) => {
+        surface!([DI] $driver<DI>);
+    };
+    ([$($generics:tt)*] $driver:ty) => {
+        impl<$($generics)*> Surface for $driver {
+            fn clear(&mut self) {
+                self.0.clear()
+            }
+            fn pixel(&mut self, x: i32, y: i32) {
+                self.0.pixel(x, y)
+            }
+            fn unset(&mut self, x: i32, y: i32) {
+                self.0.unset(x, y)
+            }
+            fn toggle(&mut self, x: i32, y: i32) {
+                self.0.toggle(x, y)
+            }
+            fn get(&self, x: i32, y: i32) -> bool {
+                self.0.get(x, y)
+            }
+            fn width(&self) -> i32 {
+                self.0.width()
+            }
+            fn height(&self) -> i32 {
+                self.0.height()
+            }
+        }
+    };
+}
+
+macro_rules! controls {
+    ($($control:ident),*) => {
+        $(controls!(@$control);)*
+    };
+    (@set_contrast) => {
+        /// Set the contrast
+        pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+            block_on(self.0.set_contrast(contrast))
+        }
+    };
+    (@set_inverted) => {
+        /// Swap the colors of filled and empty pixels
+        pub fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+            block_on(self.0.set_inverted(inverted))
+        }
+    };
+    (@set_display_on) => {
+        /// Turn the display on or off
+        pub fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+            block_on(self.0.set_display_on(on))
+        }
+    };
+    (@set_entire_on) => {
+        /// Light up all pixels regardless of the contents, for testing
+        pub fn set_entire_on(&mut self, on: bool) -> Result<(), DI::Error> {
+            block_on(self.0.set_entire_on(on))
+        }
+    };
+    (@set_flipped) => {
+        /// Rotate the display by 180 degrees in hardware
+        pub fn set_flipped(&mut self, flipped: bool) -> Result<(), DI::Error> {
+            block_on(self.0.set_flipped(flipped))
+        }
+    };
+    (@set_start_line) => {
+        /// Set the RAM row which is shown in the first row of the display
+        pub fn set_start_line(&mut self, line: u8) -> Result<(), DI::Error> {
+            block_on(self.0.set_start_line(line))
+        }
+    };
+}
+
+/// SSH1106 OLED Display
+pub struct Ssh1106<DI>(crate::Ssh1106<Blocking<DI>>);
+
+impl<DI: Interface> Ssh1106<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssh1106::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the height is not a multiple of 8 between 8 and 64 or the column offset is more
+    /// than 4.
+    pub fn with_config(interface: DI, config: Ssh1106Config) -> Result<Self, DI::Error> {
+        block_on(crate::Ssh1106::with_config(Blocking(interface), config)).map(Self)
+    }
+    /// Send the current buffer to the display
+    pub fn display(&mut self) -> Result<(), DI::Error> {
+        block_on(self.0.display())
+    }
+    controls!(
+        set_contrast,
+        set_inverted,
+        set_display_on,
+        set_entire_on,
+        set_flipped
+    );
+}
+
+impl<I: I2c> Ssh1106<I2cInterface<I>> {
+    /// Initialize the display on an I2C bus at the default address
+    ///
+    /// Shorthand for `Ssh1106::new(I2cInterface::new(i2c))`.
+    pub fn new_i2c(i2c: I) -> Result<Self, I::Error> {
+        Self::new(I2cInterface::new(i2c))
+    }
+}
+
+impl<DI> Ssh1106<DI> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: Ssh1106Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        Self(crate::Ssh1106::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Ssh1106Controller<DI>, Framebuffer<128, 64, 1024>) {
+        let (controller, buffer) = self.0.into_parts();
+        (Ssh1106Controller(controller), buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+surface!(Ssh1106);
+
+/// SSH1106 OLED controller without a framebuffer
+pub struct Ssh1106Controller<DI>(crate::Ssh1106Controller<Blocking<DI>>);
+
+impl<DI: Interface> Ssh1106Controller<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssh1106Controller::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the height is not a multiple of 8 between 8 and 64 or the column offset is more
+    /// than 4.
+    pub fn with_config(interface: DI, config: Ssh1106Config) -> Result<Self, DI::Error> {
+        block_on(crate::Ssh1106Controller::with_config(
+            Blocking(interface),
+            config,
+        ))
+        .map(Self)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        block_on(self.0.flush(frame))
+    }
+    controls!(
+        set_contrast,
+        set_inverted,
+        set_display_on,
+        set_entire_on,
+        set_flipped
+    );
+}
+
+impl<DI> Ssh1106Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    pub fn attach(interface: DI, config: Ssh1106Config) -> Self {
+        Self(crate::Ssh1106Controller::attach(
+            Blocking(interface),
+            config,
+        ))
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &Ssh1106Config {
+        self.0.config()
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+/// SSD1306 OLED Display
+///
+/// Supports modules with 128x64 and 128x32 pixels.
+pub struct Ssd1306<DI>(crate::Ssd1306<Blocking<DI>>);
+
+impl<DI: Interface> Ssd1306<DI> {
+    /// Initialize a display with 128x64 pixels
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssd1306::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize a display with 128x32 pixels
+    pub fn new_128x32(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssd1306::new_128x32(Blocking(interface))).map(Self)
+    }
+    /// Send the current buffer to the display
+    pub fn display(&mut self) -> Result<(), DI::Error> {
+        block_on(self.0.display())
+    }
+    controls!(
+        set_contrast,
+        set_inverted,
+        set_display_on,
+        set_entire_on,
+        set_flipped
+    );
+}
+
+impl<DI> Ssd1306<DI> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: Ssd1306Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        Self(crate::Ssd1306::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Ssd1306Controller<DI>, Framebuffer<128, 64, 1024>) {
+        let (controller, buffer) = self.0.into_parts();
+        (Ssd1306Controller(controller), buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+surface!(Ssd1306);
+
+/// SSD1306 OLED controller without a framebuffer
+pub struct Ssd1306Controller<DI>(crate::Ssd1306Controller<Blocking<DI>>);
+
+impl<DI: Interface> Ssd1306Controller<DI> {
+    /// Initialize a display with 128x64 pixels
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssd1306Controller::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize a display with 128x32 pixels
+    pub fn new_128x32(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Ssd1306Controller::new_128x32(Blocking(interface))).map(Self)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        block_on(self.0.flush(frame))
+    }
+    controls!(
+        set_contrast,
+        set_inverted,
+        set_display_on,
+        set_entire_on,
+        set_flipped
+    );
+}
+
+impl<DI> Ssd1306Controller<DI> {
+    /// Use a display which was already initialized with the given height
+    pub fn attach(interface: DI, height: i32) -> Self {
+        Self(crate::Ssd1306Controller::attach(
+            Blocking(interface),
+            height,
+        ))
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+/// ST7567 LCD Display, which has the same commands as the ST7565
+pub type St7567<DI> = St7565<DI>;
+
+/// ST7567 LCD controller, which has the same commands as the ST7565
+pub type St7567Controller<DI> = St7565Controller<DI>;
+
+/// ST7565 LCD Display
+pub struct St7565<DI>(crate::St7565<Blocking<DI>>);
+
+impl<DI: Interface> St7565<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::St7565::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the column offset is more than 4.
+    pub fn with_config(interface: DI, config: St7565Config) -> Result<Self, DI::Error> {
+        block_on(crate::St7565::with_config(Blocking(interface), config)).map(Self)
+    }
+    /// Send the current buffer to the display
+    pub fn display(&mut self) -> Result<(), DI::Error> {
+        block_on(self.0.display())
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_entire_on);
+}
+
+impl<DI> St7565<DI> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: St7565Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        Self(crate::St7565::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (St7565Controller<DI>, Framebuffer<128, 64, 1024>) {
+        let (controller, buffer) = self.0.into_parts();
+        (St7565Controller(controller), buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+surface!(St7565);
+
+/// ST7565 LCD controller without a framebuffer
+pub struct St7565Controller<DI>(crate::St7565Controller<Blocking<DI>>);
+
+impl<DI: Interface> St7565Controller<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::St7565Controller::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the column offset is more than 4.
+    pub fn with_config(interface: DI, config: St7565Config) -> Result<Self, DI::Error> {
+        block_on(crate::St7565Controller::with_config(
+            Blocking(interface),
+            config,
+        ))
+        .map(Self)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        block_on(self.0.flush(frame))
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_entire_on);
+}
+
+impl<DI> St7565Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    pub fn attach(interface: DI, config: St7565Config) -> Self {
+        Self(crate::St7565Controller::attach(Blocking(interface), config))
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &St7565Config {
+        self.0.config()
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+/// PCD8544 LCD Display, as used in the Nokia 5110
+pub struct Pcd8544<DI>(crate::Pcd8544<Blocking<DI>>);
+
+impl<DI: Interface> Pcd8544<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Pcd8544::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    pub fn with_config(interface: DI, config: Pcd8544Config) -> Result<Self, DI::Error> {
+        block_on(crate::Pcd8544::with_config(Blocking(interface), config)).map(Self)
+    }
+    /// Send the current buffer to the display
+    pub fn display(&mut self) -> Result<(), DI::Error> {
+        block_on(self.0.display())
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_entire_on);
+}
+
+impl<DI> Pcd8544<DI> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(controller: Pcd8544Controller<DI>, buffer: Framebuffer<84, 48, 504>) -> Self {
+        Self(crate::Pcd8544::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Pcd8544Controller<DI>, Framebuffer<84, 48, 504>) {
+        let (controller, buffer) = self.0.into_parts();
+        (Pcd8544Controller(controller), buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+surface!(Pcd8544);
+
+/// PCD8544 LCD controller without a framebuffer
+pub struct Pcd8544Controller<DI>(crate::Pcd8544Controller<Blocking<DI>>);
+
+impl<DI: Interface> Pcd8544Controller<DI> {
+    /// Initialize the display
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Pcd8544Controller::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    pub fn with_config(interface: DI, config: Pcd8544Config) -> Result<Self, DI::Error> {
+        block_on(crate::Pcd8544Controller::with_config(
+            Blocking(interface),
+            config,
+        ))
+        .map(Self)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<84, 48, 504>) -> Result<(), DI::Error> {
+        block_on(self.0.flush(frame))
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_entire_on);
+}
+
+impl<DI> Pcd8544Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    pub fn attach(interface: DI, config: Pcd8544Config) -> Self {
+        Self(crate::Pcd8544Controller::attach(
+            Blocking(interface),
+            config,
+        ))
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &Pcd8544Config {
+        self.0.config()
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+/// SH1107 OLED Display
+///
+/// Supports panels with 64x128 and 128x128 pixels.
+pub struct Sh1107<DI>(crate::Sh1107<Blocking<DI>>);
+
+impl<DI: Interface> Sh1107<DI> {
+    /// Initialize a display with 128x128 pixels
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize a display with 64x128 pixels
+    pub fn new_64x128(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107::new_64x128(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the width is not 64 or 128 or the panel does not fit into the 128 columns of RAM
+    /// with the column offset.
+    pub fn with_config(interface: DI, config: Sh1107Config) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107::with_config(Blocking(interface), config)).map(Self)
+    }
+    /// Send the current buffer to the display
+    pub fn display(&mut self) -> Result<(), DI::Error> {
+        block_on(self.0.display())
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_start_line);
+}
+
+impl<DI> Sh1107<DI> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: Sh1107Controller<DI>,
+        buffer: Framebuffer<128, 128, 2048>,
+    ) -> Self {
+        Self(crate::Sh1107::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Sh1107Controller<DI>, Framebuffer<128, 128, 2048>) {
+        let (controller, buffer) = self.0.into_parts();
+        (Sh1107Controller(controller), buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+surface!(Sh1107);
+
+/// SH1107 OLED controller without a framebuffer
+pub struct Sh1107Controller<DI>(crate::Sh1107Controller<Blocking<DI>>);
+
+impl<DI: Interface> Sh1107Controller<DI> {
+    /// Initialize a display with 128x128 pixels
+    pub fn new(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107Controller::new(Blocking(interface))).map(Self)
+    }
+    /// Initialize a display with 64x128 pixels
+    pub fn new_64x128(interface: DI) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107Controller::new_64x128(Blocking(interface))).map(Self)
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the width is not 64 or 128 or the panel does not fit into the 128 columns of RAM
+    /// with the column offset.
+    pub fn with_config(interface: DI, config: Sh1107Config) -> Result<Self, DI::Error> {
+        block_on(crate::Sh1107Controller::with_config(
+            Blocking(interface),
+            config,
+        ))
+        .map(Self)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<128, 128, 2048>) -> Result<(), DI::Error> {
+        block_on(self.0.flush(frame))
+    }
+    controls!(set_contrast, set_inverted, set_display_on, set_start_line);
+}
+
+impl<DI> Sh1107Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    pub fn attach(interface: DI, config: Sh1107Config) -> Self {
+        Self(crate::Sh1107Controller::attach(Blocking(interface), config))
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &Sh1107Config {
+        self.0.config()
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.0.release().0
+    }
+}
+
+/// Sharp Memory LCD with 128x128 pixels
+pub type Ls013b7dh03<S, P, X = NoPin> = SharpMemory<S, P, 128, 128, 2048, X>;
+/// Sharp Memory LCD with 144x168 pixels
+pub type Ls013b7dh05<S, P, X = NoPin> = SharpMemory<S, P, 144, 168, 3024, X>;
+/// Sharp Memory LCD with 400x240 pixels
+pub type Ls027b7dh01<S, P, X = NoPin> = SharpMemory<S, P, 400, 240, 12000, X>;
+
+/// Sharp Memory-in-Pixel LCD
+///
+/// The chip select is active high and driven by the driver, see [`crate::SharpMemory`].
+pub struct SharpMemory<S, P, const W: usize, const H: usize, const N: usize, X = NoPin>(
+    crate::SharpMemory<Blocking<S>, P, W, H, N, X>,
+);
+
+impl<S: SpiBus, P: OutputPin, const W: usize, const H: usize, const N: usize>
+    SharpMemory<S, P, W, H, N, NoPin<P::Error>>
+{
+    /// Initialize a display with software VCOM and clear it
+    pub fn new(spi: S, cs: P) -> Result<Self, SpiError<S::Error, P::Error>> {
+        block_on(crate::SharpMemory::new(Blocking(spi), cs)).map(Self)
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> SharpMemory<S, P, W, H, N, X>
+where
+    S: SpiBus,
+    P: OutputPin,
+    X: OutputPin<Error = P::Error>,
+{
+    /// Initialize a display with VCOM on the `EXTCOMIN` pin and clear it
+    pub fn with_extcomin(spi: S, cs: P, extcomin: X) -> Result<Self, SpiError<S::Error, P::Error>> {
+        block_on(crate::SharpMemory::with_extcomin(
+            Blocking(spi),
+            cs,
+            extcomin,
+        ))
+        .map(Self)
+    }
+    /// Send the changed lines to the display
+    pub fn display(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.display())
+    }
+    /// Clear the display memory without changing the framebuffer
+    pub fn clear_display(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.clear_display())
+    }
+    /// Invert the polarity of VCOM
+    pub fn toggle_vcom(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.toggle_vcom())
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> SharpMemory<S, P, W, H, N, X> {
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: SharpMemoryController<S, P, W, H, N, X>,
+        buffer: Framebuffer<W, H, N, Rows>,
+    ) -> Self {
+        Self(crate::SharpMemory::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(
+        self,
+    ) -> (
+        SharpMemoryController<S, P, W, H, N, X>,
+        Framebuffer<W, H, N, Rows>,
+    ) {
+        let (controller, buffer) = self.0.into_parts();
+        (SharpMemoryController(controller), buffer)
+    }
+    /// Returns the SPI bus, the chip select pin and the `EXTCOMIN` pin
+    pub fn release(self) -> (S, P, Option<X>) {
+        let (spi, cs, extcomin) = self.0.release();
+        (spi.0, cs, extcomin)
+    }
+}
+
+surface!([S, P, X, const W: usize, const H: usize, const N: usize] SharpMemory<S, P, W, H, N, X>);
+
+/// Sharp Memory-in-Pixel LCD controller without a framebuffer
+pub struct SharpMemoryController<S, P, const W: usize, const H: usize, const N: usize, X = NoPin>(
+    crate::SharpMemoryController<Blocking<S>, P, W, H, N, X>,
+);
+
+impl<S: SpiBus, P: OutputPin, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, NoPin<P::Error>>
+{
+    /// Initialize a display with software VCOM and clear it
+    pub fn new(spi: S, cs: P) -> Result<Self, SpiError<S::Error, P::Error>> {
+        block_on(crate::SharpMemoryController::new(Blocking(spi), cs)).map(Self)
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, X>
+where
+    S: SpiBus,
+    P: OutputPin,
+    X: OutputPin<Error = P::Error>,
+{
+    /// Initialize a display with VCOM on the `EXTCOMIN` pin and clear it
+    pub fn with_extcomin(spi: S, cs: P, extcomin: X) -> Result<Self, SpiError<S::Error, P::Error>> {
+        block_on(crate::SharpMemoryController::with_extcomin(
+            Blocking(spi),
+            cs,
+            extcomin,
+        ))
+        .map(Self)
+    }
+    /// Send the changed lines of a framebuffer to the display and mark it clean
+    pub fn flush(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+    ) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.flush(frame))
+    }
+    /// Clear the display memory
+    pub fn clear_display(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.clear_display())
+    }
+    /// Invert the polarity of VCOM
+    pub fn toggle_vcom(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        block_on(self.0.toggle_vcom())
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, X>
+{
+    /// Use a display which was already initialized
+    pub fn attach(spi: S, cs: P, extcomin: Option<X>) -> Self {
+        Self(crate::SharpMemoryController::attach(
+            Blocking(spi),
+            cs,
+            extcomin,
+        ))
+    }
+    /// Returns the SPI bus, the chip select pin and the `EXTCOMIN` pin
+    pub fn release(self) -> (S, P, Option<X>) {
+        let (spi, cs, extcomin) = self.0.release();
+        (spi.0, cs, extcomin)
+    }
+}
+
+type Error<DI, B, R> = crate::EPaperError<
+    <DI as Interface>::Error,
+    <B as digital::ErrorType>::Error,
+    <R as digital::ErrorType>::Error,
+>;
+
+/// 2.9" e-paper display with 128x296 pixels and an UC8151D controller
+pub type Gdew029t5<DI, B, R, D> = EPaper<crate::Uc8151, DI, B, R, D, 128, 296, 4736>;
+/// 2.13" e-paper display with 122x250 pixels and a SSD1680 controller
+pub type Gdey0213b74<DI, B, R, D> = EPaper<crate::Ssd1680, DI, B, R, D, 122, 250, 4000>;
+
+/// Monochrome e-paper display
+///
+/// Waiting for the `BUSY` pin polls the pin until the display is ready.
+pub struct EPaper<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>(
+    crate::EPaper<C, Blocking<DI>, Blocking<B>, R, Blocking<D>, W, H, N>,
+);
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> EPaper<C, DI, B, R, D, W, H, N>
+where
+    C: Controller,
+    DI: Interface,
+    B: InputPin,
+    R: OutputPin,
+    D: DelayNs,
+{
+    /// Reset and initialize the display
+    pub fn new(interface: DI, busy: B, reset: R, delay: D) -> Result<Self, Error<DI, B, R>> {
+        block_on(crate::EPaper::new(
+            Blocking(interface),
+            Blocking(busy),
+            reset,
+            Blocking(delay),
+        ))
+        .map(Self)
+    }
+    /// Update the display with a partial refresh, or a full refresh if it is due
+    pub fn display(&mut self) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.display())
+    }
+    /// Update the display with a full refresh
+    pub fn display_full(&mut self) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.display_full())
+    }
+    /// Put the display into deep sleep
+    pub fn sleep(&mut self) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.sleep())
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaper<C, DI, B, R, D, W, H, N>
+{
+    /// Set the number of partial refreshes after which a full refresh is done
+    pub fn set_full_refresh_interval(&mut self, interval: u32) {
+        self.0.set_full_refresh_interval(interval)
+    }
+    /// Combine a controller and a framebuffer
+    pub fn from_parts(
+        controller: EPaperController<C, DI, B, R, D, W, H, N>,
+        buffer: Framebuffer<W, H, N, Rows>,
+    ) -> Self {
+        Self(crate::EPaper::from_parts(controller.0, buffer))
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(
+        self,
+    ) -> (
+        EPaperController<C, DI, B, R, D, W, H, N>,
+        Framebuffer<W, H, N, Rows>,
+    ) {
+        let (controller, buffer) = self.0.into_parts();
+        (EPaperController(controller), buffer)
+    }
+    /// Returns the interface, the `BUSY` pin, the reset pin and the delay
+    pub fn release(self) -> (DI, B, R, D) {
+        let (interface, busy, reset, delay) = self.0.release();
+        (interface.0, busy.0, reset, delay.0)
+    }
+}
+
+surface!(
+    [C, DI, B, R, D, const W: usize, const H: usize, const N: usize]
+    EPaper<C, DI, B, R, D, W, H, N>
+);
+
+/// Monochrome e-paper controller without a framebuffer
+pub struct EPaperController<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>(
+    crate::EPaperController<C, Blocking<DI>, Blocking<B>, R, Blocking<D>, W, H, N>,
+);
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaperController<C, DI, B, R, D, W, H, N>
+where
+    C: Controller,
+    DI: Interface,
+    B: InputPin,
+    R: OutputPin,
+    D: DelayNs,
+{
+    /// Reset and initialize the display
+    pub fn new(interface: DI, busy: B, reset: R, delay: D) -> Result<Self, Error<DI, B, R>> {
+        block_on(crate::EPaperController::new(
+            Blocking(interface),
+            Blocking(busy),
+            reset,
+            Blocking(delay),
+        ))
+        .map(Self)
+    }
+    /// Update the display with a framebuffer and mark it clean
+    pub fn flush(&mut self, frame: &mut Framebuffer<W, H, N, Rows>) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.flush(frame))
+    }
+    /// Update the display with a framebuffer using a full refresh and mark it clean
+    pub fn flush_full(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+    ) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.flush_full(frame))
+    }
+    /// Put the display into deep sleep
+    pub fn sleep(&mut self) -> Result<(), Error<DI, B, R>> {
+        block_on(self.0.sleep())
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaperController<C, DI, B, R, D, W, H, N>
+{
+    /// Use a display without resetting it now
+    pub fn attach(interface: DI, busy: B, reset: R, delay: D) -> Self {
+        Self(crate::EPaperController::attach(
+            Blocking(interface),
+            Blocking(busy),
+            reset,
+            Blocking(delay),
+        ))
+    }
+    /// Set the number of partial refreshes after which a full refresh is done
+    pub fn set_full_refresh_interval(&mut self, interval: u32) {
+        self.0.set_full_refresh_interval(interval)
+    }
+    /// Returns the interface, the `BUSY` pin, the reset pin and the delay
+    pub fn release(self) -> (DI, B, R, D) {
+        let (interface, busy, reset, delay) = self.0.release();
+        (interface.0, busy.0, reset, delay.0)
+    }
+}
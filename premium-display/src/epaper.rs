@@ -0,0 +1,498 @@
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use premium_pixel::{Framebuffer, Rows, Surface};
+
+use crate::Interface;
+
+mod sealed {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub enum Kind {
+        Uc8151,
+        Ssd1680,
+    }
+
+    pub trait Sealed {
+        const KIND: Kind;
+    }
+}
+use sealed::Kind;
+
+/// Controller of an [`EPaper`] display
+///
+/// This trait is sealed and implemented by [`Uc8151`] and [`Ssd1680`].
+pub trait Controller: sealed::Sealed {}
+
+/// UC8151 (IL0373) e-paper controller
+#[derive(Debug, Clone, Copy)]
+pub struct Uc8151;
+
+/// SSD1680 e-paper controller
+#[derive(Debug, Clone, Copy)]
+pub struct Ssd1680;
+
+impl sealed::Sealed for Uc8151 {
+    const KIND: Kind = Kind::Uc8151;
+}
+impl sealed::Sealed for Ssd1680 {
+    const KIND: Kind = Kind::Ssd1680;
+}
+impl Controller for Uc8151 {}
+impl Controller for Ssd1680 {}
+
+/// 2.9" e-paper display with 128x296 pixels and an UC8151D controller
+pub type Gdew029t5<DI, B, R, D> = EPaper<Uc8151, DI, B, R, D, 128, 296, 4736>;
+/// 2.13" e-paper display with 122x250 pixels and a SSD1680 controller
+pub type Gdey0213b74<DI, B, R, D> = EPaper<Ssd1680, DI, B, R, D, 122, 250, 4000>;
+
+/// Error type of the e-paper driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EPaperError<I, B, R> {
+    /// Sending to the display failed
+    Interface(I),
+    /// Waiting for the busy pin failed
+    Busy(B),
+    /// Setting the reset pin failed
+    Reset(R),
+}
+
+type Error<DI, B, R> = EPaperError<
+    <DI as Interface>::Error,
+    <B as embedded_hal::digital::ErrorType>::Error,
+    <R as embedded_hal::digital::ErrorType>::Error,
+>;
+
+/// Monochrome e-paper display
+///
+/// The display is connected with a [`SpiInterface`](crate::SpiInterface), the `BUSY` pin `B`, the
+/// reset pin `R` and a delay `D`. Updates use the fast partial refresh waveform, which leaves some
+/// ghosting behind, so every few updates a full refresh is done instead.
+///
+/// The display owns a framebuffer and can be drawn on directly. All controller commands of
+/// [`EPaperController`] are available through [`Deref`].
+pub struct EPaper<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> {
+    buffer: Framebuffer<W, H, N, Rows>,
+    controller: EPaperController<C, DI, B, R, D, W, H, N>,
+}
+
+/// Monochrome e-paper controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](EPaperController::flush).
+pub struct EPaperController<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> {
+    interface: DI,
+    busy: B,
+    reset: R,
+    delay: D,
+    interval: u32,
+    partial: u32,
+    asleep: bool,
+    controller: PhantomData<C>,
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> EPaper<C, DI, B, R, D, W, H, N>
+where
+    C: Controller,
+    DI: Interface,
+    B: Wait,
+    R: OutputPin,
+    D: DelayNs,
+{
+    /// Reset and initialize the display
+    ///
+    /// The contents of the display are not changed until the first update.
+    pub async fn new(interface: DI, busy: B, reset: R, delay: D) -> Result<Self, Error<DI, B, R>> {
+        let controller = EPaperController::new(interface, busy, reset, delay).await?;
+        Ok(Self {
+            buffer: Framebuffer::new(),
+            controller,
+        })
+    }
+    /// Update the display with a partial refresh, or a full refresh if it is due
+    ///
+    /// Nothing is sent if the framebuffer was not changed.
+    pub async fn display(&mut self) -> Result<(), Error<DI, B, R>> {
+        self.controller.flush(&mut self.buffer).await
+    }
+    /// Update the display with a full refresh
+    pub async fn display_full(&mut self) -> Result<(), Error<DI, B, R>> {
+        self.controller.flush_full(&mut self.buffer).await
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaper<C, DI, B, R, D, W, H, N>
+{
+    /// Combine a controller and a framebuffer
+    ///
+    /// The framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: EPaperController<C, DI, B, R, D, W, H, N>,
+        buffer: Framebuffer<W, H, N, Rows>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(
+        self,
+    ) -> (
+        EPaperController<C, DI, B, R, D, W, H, N>,
+        Framebuffer<W, H, N, Rows>,
+    ) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface, the `BUSY` pin, the reset pin and the delay
+    pub fn release(self) -> (DI, B, R, D) {
+        self.controller.release()
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaperController<C, DI, B, R, D, W, H, N>
+where
+    C: Controller,
+    DI: Interface,
+    B: Wait,
+    R: OutputPin,
+    D: DelayNs,
+{
+    /// Reset and initialize the display
+    ///
+    /// The contents of the display are not changed until the first update.
+    pub async fn new(interface: DI, busy: B, reset: R, delay: D) -> Result<Self, Error<DI, B, R>> {
+        let mut controller = Self::attach(interface, busy, reset, delay);
+        controller.wake().await?;
+        Ok(controller)
+    }
+    /// Update the display with a framebuffer and mark it clean
+    ///
+    /// A partial refresh is used, or a full refresh if it is due. Nothing is sent if the
+    /// framebuffer was not changed, so when switching between framebuffers, call
+    /// [`Framebuffer::mark_dirty`] or use [`flush_full`](Self::flush_full).
+    pub async fn flush(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+    ) -> Result<(), Error<DI, B, R>> {
+        if frame.dirty().is_none() {
+            return Ok(());
+        }
+        self.refresh(frame, false).await
+    }
+    /// Update the display with a framebuffer using a full refresh and mark it clean
+    pub async fn flush_full(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+    ) -> Result<(), Error<DI, B, R>> {
+        self.refresh(frame, true).await
+    }
+    /// Put the display into deep sleep
+    ///
+    /// The image stays visible. The display is woken up automatically by the next update, which
+    /// is a full refresh.
+    pub async fn sleep(&mut self) -> Result<(), Error<DI, B, R>> {
+        match C::KIND {
+            Kind::Uc8151 => {
+                self.command(0x02, &[]).await?;
+                self.wait().await?;
+                self.command(0x07, &[0xa5]).await?;
+            }
+            Kind::Ssd1680 => self.command(0x10, &[0x01]).await?,
+        }
+        self.asleep = true;
+        Ok(())
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<DI, B, R>> {
+        self.reset.set_low().map_err(EPaperError::Reset)?;
+        self.delay.delay_ms(10).await;
+        self.reset.set_high().map_err(EPaperError::Reset)?;
+        self.delay.delay_ms(10).await;
+        self.wait().await?;
+        let (w, h) = (W as u16, H as u16);
+        match C::KIND {
+            Kind::Uc8151 => {
+                self.command(0x01, &[0x03, 0x00, 0x2b, 0x2b, 0x03]).await?;
+                self.command(0x06, &[0x17, 0x17, 0x17]).await?;
+                self.command(0x04, &[]).await?;
+                self.wait().await?;
+                let [h1, h0] = h.to_be_bytes();
+                self.command(0x61, &[w as u8, h1, h0]).await?;
+                self.command(0x82, &[0x12]).await?;
+            }
+            Kind::Ssd1680 => {
+                self.command(0x12, &[]).await?;
+                self.wait().await?;
+                let [y0, y1] = (h - 1).to_le_bytes();
+                self.command(0x01, &[y0, y1, 0x00]).await?;
+                self.command(0x11, &[0x03]).await?;
+                self.command(0x44, &[0x00, ((w - 1) / 8) as u8]).await?;
+                self.command(0x45, &[0x00, 0x00, y0, y1]).await?;
+                self.command(0x3c, &[0x05]).await?;
+                self.command(0x21, &[0x00, 0x80]).await?;
+                self.command(0x18, &[0x80]).await?;
+                self.wait().await?;
+            }
+        }
+        self.asleep = false;
+        self.partial = self.interval;
+        Ok(())
+    }
+
+    async fn refresh(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+        full: bool,
+    ) -> Result<(), Error<DI, B, R>> {
+        if self.asleep {
+            self.wake().await?;
+        }
+        let full = full || self.partial >= self.interval;
+        match C::KIND {
+            Kind::Uc8151 => {
+                if full {
+                    self.command(0x00, &[0x1f, 0x0d]).await?;
+                    self.command(0x50, &[0x97]).await?;
+                } else {
+                    self.command(0x00, &[0xbf, 0x0d]).await?;
+                    self.command(0x50, &[0x17]).await?;
+                    self.command(0x20, &lut::<44>(0x00)).await?;
+                    self.command(0x21, &lut::<42>(0x00)).await?;
+                    self.command(0x22, &lut::<42>(0x80)).await?;
+                    self.command(0x23, &lut::<42>(0x40)).await?;
+                    self.command(0x24, &lut::<42>(0x00)).await?;
+                }
+                self.frame(frame, 0x13).await?;
+                self.command(0x12, &[]).await?;
+                self.wait().await?;
+                self.frame(frame, 0x10).await?;
+            }
+            Kind::Ssd1680 => {
+                self.frame(frame, 0x24).await?;
+                self.command(0x22, &[if full { 0xf7 } else { 0xfc }])
+                    .await?;
+                self.command(0x20, &[]).await?;
+                self.wait().await?;
+                self.frame(frame, 0x26).await?;
+            }
+        }
+        self.partial = if full { 0 } else { self.partial + 1 };
+        frame.mark_clean();
+        Ok(())
+    }
+
+    /// Write the framebuffer into display memory, with white pixels as 1.
+    async fn frame(
+        &mut self,
+        frame: &Framebuffer<W, H, N, Rows>,
+        command: u8,
+    ) -> Result<(), Error<DI, B, R>> {
+        if C::KIND == Kind::Ssd1680 {
+            self.command(0x4e, &[0x00]).await?;
+            self.command(0x4f, &[0x00, 0x00]).await?;
+        }
+        self.command(command, &[]).await?;
+        for chunk in frame.as_bytes().chunks(32) {
+            let mut inverted = [0; 32];
+            for (a, b) in inverted.iter_mut().zip(chunk) {
+                *a = !b;
+            }
+            let inverted = &inverted[..chunk.len()];
+            self.interface
+                .data(inverted)
+                .await
+                .map_err(EPaperError::Interface)?;
+        }
+        Ok(())
+    }
+
+    async fn command(&mut self, command: u8, data: &[u8]) -> Result<(), Error<DI, B, R>> {
+        self.interface
+            .command(&[command])
+            .await
+            .map_err(EPaperError::Interface)?;
+        if !data.is_empty() {
+            self.interface
+                .data(data)
+                .await
+                .map_err(EPaperError::Interface)?;
+        }
+        Ok(())
+    }
+
+    async fn wait(&mut self) -> Result<(), Error<DI, B, R>> {
+        match C::KIND {
+            Kind::Uc8151 => self.busy.wait_for_high().await,
+            Kind::Ssd1680 => self.busy.wait_for_low().await,
+        }
+        .map_err(EPaperError::Busy)
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize>
+    EPaperController<C, DI, B, R, D, W, H, N>
+{
+    /// Use a display without resetting it now
+    ///
+    /// No commands are sent. The display is reset and initialized again on the next update,
+    /// which is a full refresh.
+    pub fn attach(interface: DI, busy: B, reset: R, delay: D) -> Self {
+        const { assert!(W <= 256 && H <= 512) };
+        Self {
+            interface,
+            busy,
+            reset,
+            delay,
+            interval: 10,
+            partial: 0,
+            asleep: true,
+            controller: PhantomData,
+        }
+    }
+    /// Set the number of partial refreshes after which a full refresh is done
+    ///
+    /// The default is 10. With 0 every update is a full refresh.
+    pub fn set_full_refresh_interval(&mut self, interval: u32) {
+        self.interval = interval;
+    }
+    /// Returns the interface, the `BUSY` pin, the reset pin and the delay
+    pub fn release(self) -> (DI, B, R, D) {
+        (self.interface, self.busy, self.reset, self.delay)
+    }
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> Deref
+    for EPaper<C, DI, B, R, D, W, H, N>
+{
+    type Target = EPaperController<C, DI, B, R, D, W, H, N>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> DerefMut
+    for EPaper<C, DI, B, R, D, W, H, N>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+/// Partial refresh waveform of the UC8151, a single short phase.
+const fn lut<const N: usize>(level: u8) -> [u8; N] {
+    let mut lut = [0; N];
+    lut[0] = level;
+    lut[1] = 0x19;
+    lut[2] = 0x01;
+    lut[5] = 0x01;
+    lut
+}
+
+impl<C, DI, B, R, D, const W: usize, const H: usize, const N: usize> Surface
+    for EPaper<C, DI, B, R, D, W, H, N>
+{
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.buffer.pixel(x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.buffer.unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.buffer.toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        W as i32
+    }
+    fn height(&self) -> i32 {
+        H as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::SpiInterface;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    fn commands(mock: &Mock) -> std::vec::Vec<u8> {
+        let transfers = mock.take();
+        transfers
+            .iter()
+            .filter_map(|t| match t {
+                Transfer::Spi(false, bytes) => Some(bytes[0]),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn refresh() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut display = block_on(Gdey0213b74::new(
+            interface,
+            mock.clone(),
+            mock.clone(),
+            mock.clone(),
+        ))
+        .unwrap();
+        let init = [0x12, 0x01, 0x11, 0x44, 0x45, 0x3c, 0x21, 0x18];
+        assert_eq!(commands(&mock), init);
+        display.set_full_refresh_interval(1);
+        block_on(display.display()).unwrap();
+        assert_eq!(commands(&mock), []);
+        let full = [0x4e, 0x4f, 0x24, 0x22, 0x20, 0x4e, 0x4f, 0x26];
+        for mode in [0xf7, 0xfc, 0xf7] {
+            display.toggle(0, 0);
+            block_on(display.display()).unwrap();
+            let transfers = mock.take();
+            let update = Transfer::Spi(false, vec![0x22]);
+            let i = transfers.iter().position(|t| *t == update).unwrap();
+            assert_eq!(transfers[i + 1], Transfer::Spi(true, vec![mode]));
+            let data = transfers
+                .iter()
+                .filter(|t| matches!(t, Transfer::Spi(true, _)));
+            assert_eq!(data.count(), 2 * (2 + 125) + 1);
+        }
+        block_on(display.sleep()).unwrap();
+        display.pixel(1, 1);
+        block_on(display.display()).unwrap();
+        let transfers = commands(&mock);
+        assert_eq!(transfers[..2], [0x10, 0x12]);
+        assert_eq!(transfers[transfers.len() - full.len()..], full);
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut controller: EPaperController<Ssd1680, _, _, _, _, 122, 250, 4000> =
+            EPaperController::attach(interface, mock.clone(), mock.clone(), mock.clone());
+        let mut frame = Framebuffer::new();
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(commands(&mock), []);
+        frame.pixel(0, 0);
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = commands(&mock);
+        assert_eq!(transfers[..2], [0x12, 0x01]);
+        assert!(transfers.contains(&0x22));
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(commands(&mock), []);
+        let (interface, busy, reset, delay) = controller.release();
+        let controller = EPaperController::attach(interface, busy, reset, delay);
+        let mut display = Gdey0213b74::from_parts(controller, frame);
+        block_on(display.display()).unwrap();
+        assert_eq!(commands(&mock)[..2], [0x12, 0x01]);
+    }
+}
@@ -0,0 +1,17 @@
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+/// Run a future to completion by polling it in a loop.
+///
+/// This is only useful for futures which never wait for a wake-up, like the drivers with a
+/// blocking or mock interface.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{I2c, Operation};
+use embedded_hal_async::spi::SpiDevice;
+
+/// Transport for sending commands and data to a display controller
+#[allow(async_fn_in_trait)]
+pub trait Interface {
+    /// Error type of the transport
+    type Error;
+    /// Send command bytes
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+    /// Send display data
+    async fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<T: Interface + ?Sized> Interface for &mut T {
+    type Error = T::Error;
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).command(bytes).await
+    }
+    async fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).data(bytes).await
+    }
+}
+
+/// I2C transport
+///
+/// Every transfer starts with a control byte, `0x00` for commands and `0x40` for data.
+#[derive(Debug)]
+pub struct I2cInterface<I> {
+    pub(crate) i2c: I,
+    pub(crate) address: u8,
+}
+
+impl<I> I2cInterface<I> {
+    /// Use the default address `0x3c`
+    pub fn new(i2c: I) -> Self {
+        Self::with_address(i2c, 0x3c)
+    }
+    /// Use a different address, usually `0x3d`
+    pub fn with_address(i2c: I, address: u8) -> Self {
+        Self { i2c, address }
+    }
+    /// Returns the I2C bus
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: I2c> Interface for I2cInterface<I> {
+    type Error = I::Error;
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut operations = [Operation::Write(&[0x00]), Operation::Write(bytes)];
+        self.i2c.transaction(self.address, &mut operations).await
+    }
+    async fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut operations = [Operation::Write(&[0x40]), Operation::Write(bytes)];
+        self.i2c.transaction(self.address, &mut operations).await
+    }
+}
+
+/// 4-wire SPI transport
+///
+/// The D/C pin is low for commands and high for data. Chip select is handled by the SPI device.
+#[derive(Debug)]
+pub struct SpiInterface<S, P> {
+    pub(crate) spi: S,
+    pub(crate) dc: P,
+}
+
+impl<S, P> SpiInterface<S, P> {
+    /// Create the interface from a SPI device and the D/C pin
+    pub fn new(spi: S, dc: P) -> Self {
+        Self { spi, dc }
+    }
+    /// Returns the SPI device and the D/C pin
+    pub fn release(self) -> (S, P) {
+        (self.spi, self.dc)
+    }
+}
+
+impl<S: SpiDevice, P: OutputPin> Interface for SpiInterface<S, P> {
+    type Error = SpiError<S::Error, P::Error>;
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.dc.set_low().map_err(SpiError::Pin)?;
+        self.spi.write(bytes).await.map_err(SpiError::Spi)
+    }
+    async fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.dc.set_high().map_err(SpiError::Pin)?;
+        self.spi.write(bytes).await.map_err(SpiError::Spi)
+    }
+}
+
+/// Error type of the SPI transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpiError<S, P> {
+    /// The SPI transfer failed
+    Spi(S),
+    /// Setting a pin failed
+    Pin(P),
+}
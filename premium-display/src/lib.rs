@@ -3,6 +3,33 @@
 #![no_std]
 #![warn(missing_docs)]
 
+#[cfg(any(test, feature = "mock"))]
+extern crate std;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod epaper;
+#[cfg(any(test, feature = "blocking", feature = "mock"))]
+mod executor;
+mod interface;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+mod oled;
+mod pages;
+mod pcd8544;
+mod sh1107;
+mod sharp;
+mod ssd1306;
 mod ssh1106;
+mod st7565;
 
-pub use ssh1106::Ssh1106;
+pub use epaper::{
+    Controller, EPaper, EPaperController, EPaperError, Gdew029t5, Gdey0213b74, Ssd1680, Uc8151,
+};
+pub use interface::{I2cInterface, Interface, SpiError, SpiInterface};
+pub use pcd8544::{Pcd8544, Pcd8544Config, Pcd8544Controller};
+pub use sh1107::{Sh1107, Sh1107Config, Sh1107Controller};
+pub use sharp::{Ls013b7dh03, Ls013b7dh05, Ls027b7dh01, NoPin, SharpMemory, SharpMemoryController};
+pub use ssd1306::{Ssd1306, Ssd1306Controller};
+pub use ssh1106::{Ssh1106, Ssh1106Config, Ssh1106Controller};
+pub use st7565::{Bias, St7565, St7565Config, St7565Controller, St7567, St7567Controller};
@@ -0,0 +1,267 @@
+//! Mock bus for testing display drivers on the host
+//!
+//! A [`Mock`] implements the I2C, SPI and output pin traits of `embedded-hal` and
+//! `embedded-hal-async` and records every write. Writes to the SPI bus are merged into one
+//! transfer until the pin changes, with the pin acting as chip select. Clones share the same
+//! record, so one clone can be given to a driver while another is used to inspect the transfers.
+//!
+//! ```
+//! use premium_display::mock::{Mock, Transfer, block_on};
+//! use premium_display::{I2cInterface, Ssh1106};
+//!
+//! let mock = Mock::new();
+//! let display = block_on(Ssh1106::new(I2cInterface::new(mock.clone()))).unwrap();
+//! assert!(matches!(mock.take()[0], Transfer::I2c(0x3c, _)));
+//! ```
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::rc::Rc;
+use std::vec::Vec;
+
+use embedded_hal::{digital, i2c, spi};
+use embedded_hal_async::{delay, digital as digital_async, i2c as i2c_async, spi as spi_async};
+
+pub use crate::executor::block_on;
+
+/// A recorded write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transfer {
+    /// I2C write with the address and the bytes, adjacent writes in one transaction are merged
+    I2c(u8, Vec<u8>),
+    /// SPI write with the level of the D/C pin and the bytes
+    Spi(bool, Vec<u8>),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    transfers: Vec<Transfer>,
+    pin: bool,
+    bus: bool,
+}
+
+/// Recording I2C bus, SPI bus, SPI device and pin
+///
+/// Waiting for the pin and delays return immediately.
+#[derive(Debug, Clone, Default)]
+pub struct Mock(Rc<RefCell<State>>);
+
+impl Mock {
+    /// Create a mock with an empty record
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns all transfers recorded so far and clears the record
+    pub fn take(&self) -> Vec<Transfer> {
+        core::mem::take(&mut self.0.borrow_mut().transfers)
+    }
+    /// Returns the level of the pin
+    pub fn is_high(&self) -> bool {
+        self.0.borrow().pin
+    }
+
+    fn i2c(&self, address: u8, operations: &mut [i2c::Operation<'_>]) {
+        let mut state = self.0.borrow_mut();
+        state.bus = false;
+        let mut write = None;
+        for operation in operations {
+            match operation {
+                i2c::Operation::Write(bytes) => {
+                    write.get_or_insert_with(Vec::new).extend_from_slice(bytes)
+                }
+                i2c::Operation::Read(buffer) => {
+                    buffer.fill(0);
+                    if let Some(bytes) = write.take() {
+                        state.transfers.push(Transfer::I2c(address, bytes));
+                    }
+                }
+            }
+        }
+        if let Some(bytes) = write {
+            state.transfers.push(Transfer::I2c(address, bytes));
+        }
+    }
+
+    fn spi(&self, operations: &mut [spi::Operation<'_, u8>]) {
+        let mut state = self.0.borrow_mut();
+        state.bus = false;
+        let mut write = Vec::new();
+        for operation in operations {
+            match operation {
+                spi::Operation::Write(bytes) => write.extend_from_slice(bytes),
+                spi::Operation::Transfer(read, bytes) => {
+                    write.extend_from_slice(bytes);
+                    read.fill(0);
+                }
+                spi::Operation::TransferInPlace(bytes) => {
+                    write.extend_from_slice(bytes);
+                    bytes.fill(0);
+                }
+                spi::Operation::Read(buffer) => buffer.fill(0),
+                spi::Operation::DelayNs(_) => {}
+            }
+        }
+        if !write.is_empty() {
+            let pin = state.pin;
+            state.transfers.push(Transfer::Spi(pin, write));
+        }
+    }
+
+    fn set(&self, pin: bool) {
+        let mut state = self.0.borrow_mut();
+        state.pin = pin;
+        state.bus = false;
+    }
+    /// Writes on the SPI bus are merged until the pin changes.
+    fn bus(&self, bytes: &[u8]) {
+        let mut state = self.0.borrow_mut();
+        let pin = state.pin;
+        if state.bus
+            && let Some(Transfer::Spi(_, write)) = state.transfers.last_mut()
+        {
+            write.extend_from_slice(bytes);
+        } else {
+            state.transfers.push(Transfer::Spi(pin, bytes.to_vec()));
+            state.bus = true;
+        }
+    }
+}
+
+impl i2c::ErrorType for Mock {
+    type Error = Infallible;
+}
+
+impl i2c::I2c for Mock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c(address, operations);
+        Ok(())
+    }
+}
+
+impl i2c_async::I2c for Mock {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c(address, operations);
+        Ok(())
+    }
+}
+
+impl spi::ErrorType for Mock {
+    type Error = Infallible;
+}
+
+impl spi::SpiDevice for Mock {
+    fn transaction(
+        &mut self,
+        operations: &mut [spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        self.spi(operations);
+        Ok(())
+    }
+}
+
+impl spi::SpiBus for Mock {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        words.fill(0);
+        Ok(())
+    }
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.bus(words);
+        Ok(())
+    }
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.bus(write);
+        read.fill(0);
+        Ok(())
+    }
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus(words);
+        words.fill(0);
+        Ok(())
+    }
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl spi_async::SpiBus for Mock {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        words.fill(0);
+        Ok(())
+    }
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.bus(words);
+        Ok(())
+    }
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.bus(write);
+        read.fill(0);
+        Ok(())
+    }
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus(words);
+        words.fill(0);
+        Ok(())
+    }
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl spi_async::SpiDevice for Mock {
+    async fn transaction(
+        &mut self,
+        operations: &mut [spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        self.spi(operations);
+        Ok(())
+    }
+}
+
+impl digital::ErrorType for Mock {
+    type Error = Infallible;
+}
+
+impl digital::OutputPin for Mock {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false);
+        Ok(())
+    }
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true);
+        Ok(())
+    }
+}
+
+impl digital_async::Wait for Mock {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl embedded_hal::delay::DelayNs for Mock {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+impl delay::DelayNs for Mock {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
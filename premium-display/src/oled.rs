@@ -0,0 +1,58 @@
+use crate::Interface;
+
+/// Power-up settings shared by the SH110x and SSD130x OLED controllers
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PowerUp {
+    /// Number of rows
+    pub height: u8,
+    /// COM pins hardware configuration
+    pub com_pins: u8,
+    /// Contrast
+    pub contrast: u8,
+    /// Pre-charge period
+    pub precharge: u8,
+    /// VCOMH deselect level
+    pub vcomh: u8,
+    /// Mirror the segments horizontally
+    pub segment_remap: bool,
+    /// Scan the COM outputs in reverse direction
+    pub com_remap: bool,
+}
+
+/// Initialize an SH1106 or SSD1306 compatible controller and turn the display on
+///
+/// The memory is set to horizontal addressing mode, which the SH1106 ignores.
+pub(crate) async fn power_up<DI: Interface>(
+    interface: &mut DI,
+    power: PowerUp,
+) -> Result<(), DI::Error> {
+    interface
+        .command(&[
+            0xae,
+            0xd5,
+            0x80,
+            0xa8,
+            power.height - 1,
+            0xd3,
+            0x00,
+            0x40,
+            0x8d,
+            0x14,
+            0x20,
+            0x00,
+            0xa0 | power.segment_remap as u8,
+            0xc0 | (power.com_remap as u8) << 3,
+            0xda,
+            power.com_pins,
+            0x81,
+            power.contrast,
+            0xd9,
+            power.precharge,
+            0xdb,
+            power.vcomh,
+            0xa4,
+            0xa6,
+            0xaf,
+        ])
+        .await
+}
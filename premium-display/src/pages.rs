@@ -0,0 +1,37 @@
+use premium_pixel::Framebuffer;
+
+use crate::Interface;
+
+/// Send the changed part of a framebuffer to a controller with paged memory and mark it clean
+///
+/// Each changed page is sent separately, starting at the first changed column. Only the visible
+/// area given by `width` and `height` is sent. `address` returns the command which selects a page
+/// and a column of the framebuffer, so it has to add the column offset of the panel.
+pub(crate) async fn flush<DI, const W: usize, const H: usize, const N: usize, const M: usize>(
+    interface: &mut DI,
+    frame: &mut Framebuffer<W, H, N>,
+    width: usize,
+    height: usize,
+    address: impl Fn(u8, u8) -> [u8; M],
+) -> Result<(), DI::Error>
+where
+    DI: Interface,
+{
+    let Some(dirty) = frame.dirty() else {
+        return Ok(());
+    };
+    let columns = dirty.x as usize..((dirty.x + dirty.width) as usize).min(width);
+    let end = ((dirty.y + dirty.height) as usize).div_ceil(8);
+    let pages = dirty.y as usize / 8..end.min(height.div_ceil(8));
+    if !columns.is_empty() {
+        for page in pages {
+            interface
+                .command(&address(page as u8, columns.start as u8))
+                .await?;
+            let row = &frame.as_bytes()[page * W..(page + 1) * W];
+            interface.data(&row[columns.clone()]).await?;
+        }
+    }
+    frame.mark_clean();
+    Ok(())
+}
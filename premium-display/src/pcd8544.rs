@@ -0,0 +1,285 @@
+use core::ops::{Deref, DerefMut};
+
+use premium_pixel::{Framebuffer, Surface};
+
+use crate::{Interface, pages};
+
+/// PCD8544 LCD Display, as used in the Nokia 5110
+///
+/// The display has 84x48 pixels and is connected with a [`SpiInterface`](crate::SpiInterface).
+/// The reset pulse required after power-on has to be generated before calling
+/// [`new`](Self::new). All controller commands of [`Pcd8544Controller`] are available through
+/// [`Deref`].
+pub struct Pcd8544<DI> {
+    buffer: Framebuffer<84, 48, 504>,
+    controller: Pcd8544Controller<DI>,
+}
+
+/// PCD8544 LCD controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](Pcd8544Controller::flush).
+pub struct Pcd8544Controller<DI> {
+    config: Pcd8544Config,
+    power_down: bool,
+    refresh: bool,
+    interface: DI,
+}
+
+/// Configuration of a [`Pcd8544`] display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pcd8544Config {
+    /// Operating voltage, which sets the contrast, from 0 to 127
+    pub vop: u8,
+    /// Bias system, from 0 to 7
+    pub bias: u8,
+    /// Temperature coefficient, from 0 to 3
+    pub temperature_coefficient: u8,
+    /// Show filled pixels bright on a dark background
+    pub inverted: bool,
+}
+
+impl Default for Pcd8544Config {
+    fn default() -> Self {
+        Self {
+            vop: 0x30,
+            bias: 4,
+            temperature_coefficient: 0,
+            inverted: false,
+        }
+    }
+}
+
+impl<DI: Interface> Pcd8544<DI> {
+    /// Initialize the display
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Pcd8544Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    pub async fn with_config(interface: DI, config: Pcd8544Config) -> Result<Self, DI::Error> {
+        let controller = Pcd8544Controller::with_config(interface, config).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the current buffer to the display
+    ///
+    /// Only the banks and columns which were changed since the last call are sent.
+    pub async fn display(&mut self) -> Result<(), DI::Error> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<DI> Pcd8544<DI> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(controller: Pcd8544Controller<DI>, buffer: Framebuffer<84, 48, 504>) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Pcd8544Controller<DI>, Framebuffer<84, 48, 504>) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.controller.release()
+    }
+}
+
+impl<DI: Interface> Pcd8544Controller<DI> {
+    /// Initialize the display
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Pcd8544Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    pub async fn with_config(interface: DI, config: Pcd8544Config) -> Result<Self, DI::Error> {
+        let mut controller = Self::attach(interface, config);
+        controller
+            .interface
+            .command(&[
+                0x21,
+                0x80 | config.vop & 0x7f,
+                0x04 | config.temperature_coefficient & 0x03,
+                0x10 | config.bias & 0x07,
+                0x20,
+                0x0c | config.inverted as u8,
+            ])
+            .await?;
+        Ok(controller)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created or attached.
+    /// When switching between framebuffers, call [`Framebuffer::mark_dirty`] to send the whole
+    /// frame.
+    pub async fn flush(&mut self, frame: &mut Framebuffer<84, 48, 504>) -> Result<(), DI::Error> {
+        if self.refresh {
+            frame.mark_dirty();
+        }
+        pages::flush(&mut self.interface, frame, 84, 48, |bank, column| {
+            [0x40 | bank, 0x80 | column]
+        })
+        .await?;
+        self.refresh = false;
+        Ok(())
+    }
+    /// Set the operating voltage, from 0 to 127
+    pub async fn set_contrast(&mut self, vop: u8) -> Result<(), DI::Error> {
+        let function = 0x20 | (self.power_down as u8) << 2;
+        self.interface
+            .command(&[function | 0x01, 0x80 | vop & 0x7f, function])
+            .await?;
+        self.config.vop = vop & 0x7f;
+        Ok(())
+    }
+    /// Show filled pixels bright on a dark background
+    pub async fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0x0c | inverted as u8]).await?;
+        self.config.inverted = inverted;
+        Ok(())
+    }
+    /// Turn the display on or off
+    ///
+    /// The display keeps its contents in power-down mode.
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0x20 | (!on as u8) << 2]).await?;
+        self.power_down = !on;
+        Ok(())
+    }
+    /// Turn on all pixels regardless of the contents, for testing
+    pub async fn set_entire_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        let mode = if on {
+            0x09
+        } else {
+            0x0c | self.config.inverted as u8
+        };
+        self.interface.command(&[mode]).await
+    }
+}
+
+impl<DI> Pcd8544Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    ///
+    /// No commands are sent and the display is assumed to be turned on. After
+    /// [`release`](Self::release) the interface can be attached again with the
+    /// [`config`](Self::config) of the old controller.
+    pub fn attach(interface: DI, config: Pcd8544Config) -> Self {
+        Self {
+            config,
+            power_down: false,
+            refresh: true,
+            interface,
+        }
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &Pcd8544Config {
+        &self.config
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+impl<DI> Deref for Pcd8544<DI> {
+    type Target = Pcd8544Controller<DI>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<DI> DerefMut for Pcd8544<DI> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<DI> Surface for Pcd8544<DI> {
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.buffer.pixel(x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.buffer.unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.buffer.toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        84
+    }
+    fn height(&self) -> i32 {
+        48
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::SpiInterface;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut display = block_on(Pcd8544::new(interface)).unwrap();
+        let init = vec![0x21, 0xb0, 0x04, 0x14, 0x20, 0x0c];
+        assert_eq!(mock.take(), [Transfer::Spi(false, init)]);
+        block_on(display.display()).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 12);
+        assert_eq!(transfers[10], Transfer::Spi(false, vec![0x45, 0x80]));
+        display.pixel(83, 47);
+        block_on(display.display()).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0x45, 0xd3]),
+                Transfer::Spi(true, vec![0x80]),
+            ]
+        );
+        block_on(display.set_contrast(0x7f)).unwrap();
+        assert_eq!(mock.take(), [Transfer::Spi(false, vec![0x21, 0xff, 0x20])]);
+        block_on(display.set_display_on(false)).unwrap();
+        block_on(display.set_contrast(0x40)).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0x24]),
+                Transfer::Spi(false, vec![0x25, 0xc0, 0x24]),
+            ]
+        );
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut controller = block_on(Pcd8544Controller::new(interface)).unwrap();
+        block_on(controller.set_inverted(true)).unwrap();
+        let config = *controller.config();
+        let mut controller = Pcd8544Controller::attach(controller.release(), config);
+        let mut frame = Framebuffer::new();
+        frame.pixel(0, 0);
+        mock.take();
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(mock.take().len(), 12);
+        block_on(controller.set_entire_on(true)).unwrap();
+        block_on(controller.set_entire_on(false)).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0x09]),
+                Transfer::Spi(false, vec![0x0d]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use core::ops::{Deref, DerefMut};
+
+use premium_pixel::{Framebuffer, Surface};
+
+use crate::{Interface, pages};
+
+/// SH1107 OLED Display
+///
+/// Supports panels with 64x128 and 128x128 pixels over I2C or SPI. The memory is organized in 16
+/// pages of 8 rows. All controller commands of [`Sh1107Controller`] are available through
+/// [`Deref`].
+pub struct Sh1107<DI> {
+    buffer: Framebuffer<128, 128, 2048>,
+    controller: Sh1107Controller<DI>,
+}
+
+/// SH1107 OLED controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](Sh1107Controller::flush). Panels with 64 columns only use the left half of the
+/// framebuffer.
+pub struct Sh1107Controller<DI> {
+    config: Sh1107Config,
+    refresh: bool,
+    interface: DI,
+}
+
+/// Configuration of an [`Sh1107`] display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sh1107Config {
+    /// Number of columns, 64 or 128
+    pub width: u8,
+    /// First column of the panel in RAM
+    pub column_offset: u8,
+    /// RAM row which is shown in the first row of the display
+    pub start_line: u8,
+    /// Vertical shift of the COM outputs
+    pub display_offset: u8,
+    /// Contrast
+    pub contrast: u8,
+    /// Mirror the segments horizontally
+    pub segment_remap: bool,
+    /// Scan the COM outputs in reverse direction
+    pub com_remap: bool,
+}
+
+impl Sh1107Config {
+    /// Configuration for panels with 128x128 pixels
+    pub const fn size_128x128() -> Self {
+        Self {
+            width: 128,
+            column_offset: 0,
+            start_line: 0,
+            display_offset: 0,
+            contrast: 0x4f,
+            segment_remap: false,
+            com_remap: false,
+        }
+    }
+    /// Configuration for panels with 64x128 pixels
+    pub const fn size_64x128() -> Self {
+        Self {
+            width: 64,
+            display_offset: 0x60,
+            contrast: 0x2f,
+            ..Self::size_128x128()
+        }
+    }
+}
+
+impl Default for Sh1107Config {
+    fn default() -> Self {
+        Self::size_128x128()
+    }
+}
+
+impl<DI: Interface> Sh1107<DI> {
+    /// Initialize a display with 128x128 pixels
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Sh1107Config::size_128x128()).await
+    }
+    /// Initialize a display with 64x128 pixels
+    pub async fn new_64x128(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Sh1107Config::size_64x128()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the width is not 64 or 128 or the panel does not fit into the 128 columns of RAM
+    /// with the column offset.
+    pub async fn with_config(interface: DI, config: Sh1107Config) -> Result<Self, DI::Error> {
+        let controller = Sh1107Controller::with_config(interface, config).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the current buffer to the display
+    ///
+    /// Only the pages and columns which were changed since the last call are sent.
+    pub async fn display(&mut self) -> Result<(), DI::Error> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<DI> Sh1107<DI> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: Sh1107Controller<DI>,
+        buffer: Framebuffer<128, 128, 2048>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Sh1107Controller<DI>, Framebuffer<128, 128, 2048>) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.controller.release()
+    }
+}
+
+impl<DI: Interface> Sh1107Controller<DI> {
+    /// Initialize a display with 128x128 pixels
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Sh1107Config::size_128x128()).await
+    }
+    /// Initialize a display with 64x128 pixels
+    pub async fn new_64x128(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Sh1107Config::size_64x128()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the width is not 64 or 128 or the panel does not fit into the 128 columns of RAM
+    /// with the column offset.
+    pub async fn with_config(interface: DI, config: Sh1107Config) -> Result<Self, DI::Error> {
+        let mut controller = Self::attach(interface, config);
+        controller
+            .interface
+            .command(&[
+                0xae,
+                0xdc,
+                config.start_line & 0x7f,
+                0x81,
+                config.contrast,
+                0x20,
+                0xa0 | config.segment_remap as u8,
+                0xc0 | (config.com_remap as u8) << 3,
+                0xa8,
+                config.width - 1,
+                0xd3,
+                config.display_offset & 0x7f,
+                0xd5,
+                0x51,
+                0xd9,
+                0x22,
+                0xdb,
+                0x35,
+                0xa4,
+                0xa6,
+                0xaf,
+            ])
+            .await?;
+        Ok(controller)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created or attached.
+    /// When switching between framebuffers, call [`Framebuffer::mark_dirty`] to send the whole
+    /// frame.
+    pub async fn flush(
+        &mut self,
+        frame: &mut Framebuffer<128, 128, 2048>,
+    ) -> Result<(), DI::Error> {
+        if self.refresh {
+            frame.mark_dirty();
+        }
+        let (width, offset) = (self.config.width as usize, self.config.column_offset);
+        pages::flush(&mut self.interface, frame, width, 128, |page, column| {
+            let column = offset + column;
+            [0xb0 | page, column & 0x0f, 0x10 | column >> 4]
+        })
+        .await?;
+        self.refresh = false;
+        Ok(())
+    }
+    /// Set the contrast
+    pub async fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.interface.command(&[0x81, contrast]).await?;
+        self.config.contrast = contrast;
+        Ok(())
+    }
+    /// Show filled pixels dark on a bright background
+    pub async fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa6 | inverted as u8]).await
+    }
+    /// Turn the display on or off
+    ///
+    /// The display keeps its contents while it is off and uses very little power.
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xae | on as u8]).await
+    }
+    /// Set the RAM row which is shown in the first row of the display
+    ///
+    /// This can be used for hardware scrolling.
+    pub async fn set_start_line(&mut self, line: u8) -> Result<(), DI::Error> {
+        self.interface.command(&[0xdc, line & 0x7f]).await?;
+        self.config.start_line = line & 0x7f;
+        Ok(())
+    }
+}
+
+impl<DI> Sh1107Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    ///
+    /// No commands are sent, so after [`release`](Self::release) the interface can be attached
+    /// again with the [`config`](Self::config) of the old controller. Panics if the width is not
+    /// 64 or 128 or the panel does not fit into the 128 columns of RAM with the column offset.
+    pub fn attach(interface: DI, config: Sh1107Config) -> Self {
+        assert!(config.width == 64 || config.width == 128);
+        assert!(config.column_offset <= 128 - config.width);
+        Self {
+            config,
+            refresh: true,
+            interface,
+        }
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &Sh1107Config {
+        &self.config
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+impl<DI> Deref for Sh1107<DI> {
+    type Target = Sh1107Controller<DI>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<DI> DerefMut for Sh1107<DI> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<DI> Surface for Sh1107<DI> {
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        if x < self.width() {
+            self.buffer.pixel(x, y)
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        if x < self.width() {
+            self.buffer.unset(x, y)
+        }
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        if x < self.width() {
+            self.buffer.toggle(x, y)
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        x < self.width() && self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        self.controller.config.width as i32
+    }
+    fn height(&self) -> i32 {
+        128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{Mock, Transfer, block_on};
+    use crate::{I2cInterface, SpiInterface};
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let mut display = block_on(Sh1107::new_64x128(I2cInterface::new(mock.clone()))).unwrap();
+        let Transfer::I2c(0x3c, init) = &mock.take()[0] else {
+            panic!("expected init sequence");
+        };
+        assert_eq!(init[2..4], [0xdc, 0x00]);
+        assert_eq!(init[9..11], [0xa8, 0x3f]);
+        assert_eq!(init[11..13], [0xd3, 0x60]);
+        block_on(display.display()).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 32);
+        assert_eq!(
+            transfers[30],
+            Transfer::I2c(0x3c, vec![0x00, 0xbf, 0x00, 0x10])
+        );
+        assert!(matches!(&transfers[31], Transfer::I2c(0x3c, page) if page.len() == 65));
+
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut display = block_on(Sh1107::new(interface)).unwrap();
+        assert!(
+            matches!(&mock.take()[0], Transfer::Spi(false, init) if init[8..10] == [0xa8, 0x7f])
+        );
+        block_on(display.display()).unwrap();
+        mock.take();
+        display.pixel(100, 127);
+        block_on(display.set_start_line(32)).unwrap();
+        block_on(display.display()).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0xdc, 0x20]),
+                Transfer::Spi(false, vec![0xbf, 0x04, 0x16]),
+                Transfer::Spi(true, vec![0x80]),
+            ]
+        );
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let interface = I2cInterface::new(mock.clone());
+        let mut controller = block_on(Sh1107Controller::new_64x128(interface)).unwrap();
+        block_on(controller.set_start_line(8)).unwrap();
+        let config = *controller.config();
+        assert_eq!(config.start_line, 8);
+        let mut controller = Sh1107Controller::attach(controller.release(), config);
+        let mut frame = Framebuffer::new();
+        frame.pixel(100, 0);
+        mock.take();
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 32);
+        assert!(matches!(&transfers[1], Transfer::I2c(0x3c, page) if page.len() == 65));
+        frame.pixel(100, 8);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(mock.take(), []);
+    }
+}
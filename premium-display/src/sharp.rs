@@ -0,0 +1,402 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::digital::{self, ErrorType, OutputPin};
+use embedded_hal_async::spi::SpiBus;
+use premium_pixel::{Framebuffer, Rows, Surface};
+
+use crate::SpiError;
+
+/// Sharp Memory LCD with 128x128 pixels
+pub type Ls013b7dh03<S, P, X = NoPin> = SharpMemory<S, P, 128, 128, 2048, X>;
+/// Sharp Memory LCD with 144x168 pixels
+pub type Ls013b7dh05<S, P, X = NoPin> = SharpMemory<S, P, 144, 168, 3024, X>;
+/// Sharp Memory LCD with 400x240 pixels
+pub type Ls027b7dh01<S, P, X = NoPin> = SharpMemory<S, P, 400, 240, 12000, X>;
+
+/// Sharp Memory-in-Pixel LCD
+///
+/// The display is connected to a SPI bus with the default MSB first bit order. The chip select of
+/// these displays is active high, so it is driven by the driver instead of a SPI device.
+///
+/// The panel needs the polarity of VCOM inverted at least once per second. By default this is done
+/// in software with every update, idle displays need
+/// [`toggle_vcom`](SharpMemoryController::toggle_vcom) to be called regularly. Displays with
+/// `EXTMODE` tied high use an `EXTCOMIN` pin instead, which can also be toggled by the driver or
+/// by a timer. The `EXTCOMIN` pin can have a different type than the chip select pin, but it has
+/// to use the same error type. Displays with software VCOM use [`NoPin`] instead.
+///
+/// The display owns a framebuffer and can be drawn on directly. All controller commands of
+/// [`SharpMemoryController`] are available through [`Deref`].
+pub struct SharpMemory<S, P, const W: usize, const H: usize, const N: usize, X = NoPin> {
+    buffer: Framebuffer<W, H, N, Rows>,
+    controller: SharpMemoryController<S, P, W, H, N, X>,
+}
+
+/// Sharp Memory-in-Pixel LCD controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](SharpMemoryController::flush).
+pub struct SharpMemoryController<S, P, const W: usize, const H: usize, const N: usize, X = NoPin> {
+    spi: S,
+    cs: P,
+    extcomin: Option<X>,
+    vcom: bool,
+    refresh: bool,
+}
+
+/// Placeholder for the missing `EXTCOMIN` pin of a [`SharpMemory`] display with software VCOM
+///
+/// The error type matches the error type of the chip select pin.
+#[derive(Debug)]
+pub struct NoPin<E = Infallible>(PhantomData<E>);
+
+impl<E: digital::Error> ErrorType for NoPin<E> {
+    type Error = E;
+}
+
+impl<E: digital::Error> OutputPin for NoPin<E> {
+    fn set_low(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+    fn set_high(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+const UPDATE: u8 = 0x80;
+const VCOM: u8 = 0x40;
+const CLEAR: u8 = 0x20;
+
+impl<S: SpiBus, P: OutputPin, const W: usize, const H: usize, const N: usize>
+    SharpMemory<S, P, W, H, N, NoPin<P::Error>>
+{
+    /// Initialize a display with software VCOM and clear it
+    pub async fn new(spi: S, cs: P) -> Result<Self, SpiError<S::Error, P::Error>> {
+        let controller = SharpMemoryController::new(spi, cs).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> SharpMemory<S, P, W, H, N, X>
+where
+    S: SpiBus,
+    P: OutputPin,
+    X: OutputPin<Error = P::Error>,
+{
+    /// Initialize a display with VCOM on the `EXTCOMIN` pin and clear it
+    pub async fn with_extcomin(
+        spi: S,
+        cs: P,
+        extcomin: X,
+    ) -> Result<Self, SpiError<S::Error, P::Error>> {
+        let controller = SharpMemoryController::with_extcomin(spi, cs, extcomin).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the changed lines to the display
+    pub async fn display(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> SharpMemory<S, P, W, H, N, X> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: SharpMemoryController<S, P, W, H, N, X>,
+        buffer: Framebuffer<W, H, N, Rows>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(
+        self,
+    ) -> (
+        SharpMemoryController<S, P, W, H, N, X>,
+        Framebuffer<W, H, N, Rows>,
+    ) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the SPI bus, the chip select pin and the `EXTCOMIN` pin
+    pub fn release(self) -> (S, P, Option<X>) {
+        self.controller.release()
+    }
+}
+
+impl<S: SpiBus, P: OutputPin, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, NoPin<P::Error>>
+{
+    /// Initialize a display with software VCOM and clear it
+    pub async fn new(spi: S, cs: P) -> Result<Self, SpiError<S::Error, P::Error>> {
+        Self::init(spi, cs, None).await
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, X>
+where
+    S: SpiBus,
+    P: OutputPin,
+    X: OutputPin<Error = P::Error>,
+{
+    /// Initialize a display with VCOM on the `EXTCOMIN` pin and clear it
+    pub async fn with_extcomin(
+        spi: S,
+        cs: P,
+        extcomin: X,
+    ) -> Result<Self, SpiError<S::Error, P::Error>> {
+        Self::init(spi, cs, Some(extcomin)).await
+    }
+    async fn init(
+        spi: S,
+        mut cs: P,
+        extcomin: Option<X>,
+    ) -> Result<Self, SpiError<S::Error, P::Error>> {
+        cs.set_low().map_err(SpiError::Pin)?;
+        let mut controller = Self::attach(spi, cs, extcomin);
+        controller.clear_display().await?;
+        Ok(controller)
+    }
+    /// Send the changed lines of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created or attached
+    /// and after [`clear_display`](Self::clear_display). When switching between framebuffers,
+    /// call [`Framebuffer::mark_dirty`] to send the whole frame.
+    pub async fn flush(
+        &mut self,
+        frame: &mut Framebuffer<W, H, N, Rows>,
+    ) -> Result<(), SpiError<S::Error, P::Error>> {
+        if self.refresh {
+            frame.mark_dirty();
+        }
+        let Some(dirty) = frame.dirty() else {
+            return Ok(());
+        };
+        let stride = W.div_ceil(8);
+        let command = UPDATE | self.next_vcom()?;
+        self.begin()?;
+        self.spi.write(&[command]).await.map_err(SpiError::Spi)?;
+        for y in dirty.y as usize..(dirty.y + dirty.height) as usize {
+            let address = (y as u8 + 1).reverse_bits();
+            self.spi.write(&[address]).await.map_err(SpiError::Spi)?;
+            let line = &frame.as_bytes()[y * stride..(y + 1) * stride];
+            for chunk in line.chunks(32) {
+                let mut inverted = [0; 32];
+                for (a, b) in inverted.iter_mut().zip(chunk) {
+                    *a = !b;
+                }
+                let inverted = &inverted[..chunk.len()];
+                self.spi.write(inverted).await.map_err(SpiError::Spi)?;
+            }
+            self.spi.write(&[0x00]).await.map_err(SpiError::Spi)?;
+        }
+        self.spi.write(&[0x00]).await.map_err(SpiError::Spi)?;
+        self.end().await?;
+        frame.mark_clean();
+        self.refresh = false;
+        Ok(())
+    }
+    /// Clear the display memory
+    ///
+    /// The framebuffer is not changed, the whole frame is sent again on the next flush.
+    pub async fn clear_display(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        let command = CLEAR | self.next_vcom()?;
+        self.begin()?;
+        self.spi
+            .write(&[command, 0x00])
+            .await
+            .map_err(SpiError::Spi)?;
+        self.end().await?;
+        self.refresh = true;
+        Ok(())
+    }
+    /// Invert the polarity of VCOM
+    ///
+    /// Call this about once per second when the display is not updated.
+    pub async fn toggle_vcom(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        let command = self.next_vcom()?;
+        if self.extcomin.is_some() {
+            return Ok(());
+        }
+        self.begin()?;
+        self.spi
+            .write(&[command, 0x00])
+            .await
+            .map_err(SpiError::Spi)?;
+        self.end().await
+    }
+    /// Inverts VCOM and returns the VCOM bit for the next command.
+    fn next_vcom(&mut self) -> Result<u8, SpiError<S::Error, P::Error>> {
+        self.vcom = !self.vcom;
+        match &mut self.extcomin {
+            Some(pin) => pin.set_state(self.vcom.into()).map(|_| 0),
+            None => Ok(if self.vcom { VCOM } else { 0 }),
+        }
+        .map_err(SpiError::Pin)
+    }
+    fn begin(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        self.cs.set_high().map_err(SpiError::Pin)
+    }
+    async fn end(&mut self) -> Result<(), SpiError<S::Error, P::Error>> {
+        self.spi.flush().await.map_err(SpiError::Spi)?;
+        self.cs.set_low().map_err(SpiError::Pin)
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize>
+    SharpMemoryController<S, P, W, H, N, X>
+{
+    /// Use a display which was already initialized
+    ///
+    /// No commands are sent and the chip select pin has to be low already. Pass `None` as the
+    /// `EXTCOMIN` pin for displays with software VCOM.
+    pub fn attach(spi: S, cs: P, extcomin: Option<X>) -> Self {
+        const { assert!(H <= 255) };
+        Self {
+            spi,
+            cs,
+            extcomin,
+            vcom: false,
+            refresh: true,
+        }
+    }
+    /// Returns the SPI bus, the chip select pin and the `EXTCOMIN` pin
+    pub fn release(self) -> (S, P, Option<X>) {
+        (self.spi, self.cs, self.extcomin)
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> Deref
+    for SharpMemory<S, P, W, H, N, X>
+{
+    type Target = SharpMemoryController<S, P, W, H, N, X>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<S, P, X, const W: usize, const H: usize, const N: usize> DerefMut
+    for SharpMemory<S, P, W, H, N, X>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<S, P, X, const W: usize, const H: usize, const N: usize> Surface
+    for SharpMemory<S, P, W, H, N, X>
+{
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.buffer.pixel(x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.buffer.unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.buffer.toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        W as i32
+    }
+    fn height(&self) -> i32 {
+        H as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let mut display = block_on(Ls013b7dh03::new(mock.clone(), mock.clone())).unwrap();
+        assert_eq!(mock.take(), [Transfer::Spi(true, vec![0x60, 0x00])]);
+        assert!(!mock.is_high());
+        display.pixel(0, 2);
+        block_on(display.display()).unwrap();
+        let transfers = mock.take();
+        let Transfer::Spi(true, frame) = &transfers[0] else {
+            panic!("expected a frame");
+        };
+        assert_eq!(frame.len(), 2 + 128 * 18);
+        assert_eq!(frame[0], 0x80);
+        let lines: Vec<_> = frame[1..frame.len() - 1].chunks(18).collect();
+        assert_eq!(
+            (lines[0][0], lines[1][0], lines[127][0]),
+            (0x80, 0x40, 0x01)
+        );
+        assert_eq!((lines[2][1], lines[2][2], lines[2][17]), (0x7f, 0xff, 0x00));
+        display.unset(0, 2);
+        block_on(display.display()).unwrap();
+        let mut line = vec![0xc0, 0xc0];
+        line.extend([0xff; 16]);
+        line.extend([0x00, 0x00]);
+        assert_eq!(mock.take(), [Transfer::Spi(true, line)]);
+        block_on(display.toggle_vcom()).unwrap();
+        assert_eq!(mock.take(), [Transfer::Spi(true, vec![0x00, 0x00])]);
+    }
+
+    #[test]
+    fn extcomin() {
+        let (mock, extcomin) = (Mock::new(), Mock::new());
+        let mut pin = extcomin.clone();
+        let mut display = block_on(Ls013b7dh03::with_extcomin(
+            mock.clone(),
+            mock.clone(),
+            &mut pin,
+        ))
+        .unwrap();
+        assert_eq!(mock.take(), [Transfer::Spi(true, vec![0x20, 0x00])]);
+        assert!(extcomin.is_high());
+        block_on(display.toggle_vcom()).unwrap();
+        assert_eq!(mock.take(), []);
+        assert!(!extcomin.is_high());
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let mut controller = block_on(SharpMemoryController::<_, _, 128, 128, 2048>::new(
+            mock.clone(),
+            mock.clone(),
+        ))
+        .unwrap();
+        let mut frame = Framebuffer::new();
+        mock.take();
+        let lines = |transfers: Vec<Transfer>| match &transfers[..] {
+            [Transfer::Spi(true, frame)] => (frame.len() - 2) / 18,
+            _ => panic!("expected a frame"),
+        };
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(lines(mock.take()), 128);
+        frame.pixel(0, 5);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(lines(mock.take()), 1);
+        block_on(controller.clear_display()).unwrap();
+        assert_eq!(mock.take().len(), 1);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(lines(mock.take()), 128);
+        let (spi, cs, extcomin) = controller.release();
+        let mut controller =
+            SharpMemoryController::<_, _, 128, 128, 2048>::attach(spi, cs, extcomin);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(lines(mock.take()), 128);
+        let display = SharpMemory::from_parts(controller, frame);
+        let (_, frame) = display.into_parts();
+        assert!(frame.dirty().is_some());
+    }
+}
@@ -0,0 +1,300 @@
+use core::ops::{Deref, DerefMut};
+
+use premium_pixel::{Framebuffer, Surface};
+
+use crate::Interface;
+use crate::oled::{self, PowerUp};
+
+/// SSD1306 OLED Display
+///
+/// Supports modules with 128x64 and 128x32 pixels. The display owns a framebuffer and can be drawn
+/// on directly. All controller commands of [`Ssd1306Controller`] are available through [`Deref`].
+pub struct Ssd1306<DI> {
+    buffer: Framebuffer<128, 64, 1024>,
+    controller: Ssd1306Controller<DI>,
+}
+
+/// SSD1306 OLED controller without a framebuffer
+///
+/// The changed part of a frame is sent in a single transfer using horizontal addressing mode.
+pub struct Ssd1306Controller<DI> {
+    height: i32,
+    refresh: bool,
+    interface: DI,
+}
+
+impl<DI: Interface> Ssd1306<DI> {
+    /// Initialize a display with 128x64 pixels
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        let controller = Ssd1306Controller::new(interface).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Initialize a display with 128x32 pixels
+    pub async fn new_128x32(interface: DI) -> Result<Self, DI::Error> {
+        let controller = Ssd1306Controller::new_128x32(interface).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the current buffer to the display
+    ///
+    /// Only the pages and columns which were changed since the last call are sent.
+    pub async fn display(&mut self) -> Result<(), DI::Error> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<DI> Ssd1306<DI> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: Ssd1306Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Ssd1306Controller<DI>, Framebuffer<128, 64, 1024>) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.controller.release()
+    }
+}
+
+impl<DI: Interface> Ssd1306Controller<DI> {
+    /// Initialize a display with 128x64 pixels
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::init(interface, 64, 0x12, 0xcf).await
+    }
+    /// Initialize a display with 128x32 pixels
+    pub async fn new_128x32(interface: DI) -> Result<Self, DI::Error> {
+        Self::init(interface, 32, 0x02, 0x8f).await
+    }
+    async fn init(
+        interface: DI,
+        height: i32,
+        com_pins: u8,
+        contrast: u8,
+    ) -> Result<Self, DI::Error> {
+        let mut controller = Self::attach(interface, height);
+        oled::power_up(
+            &mut controller.interface,
+            PowerUp {
+                height: height as u8,
+                com_pins,
+                contrast,
+                precharge: 0xf1,
+                vcomh: 0x40,
+                segment_remap: true,
+                com_remap: true,
+            },
+        )
+        .await?;
+        Ok(controller)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created, attached or
+    /// flipped. Afterwards the framebuffer only knows what changed since it was last flushed, so
+    /// when switching between framebuffers, call [`Framebuffer::mark_dirty`] to send the whole
+    /// frame.
+    pub async fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        if self.refresh {
+            frame.mark_dirty();
+        }
+        let Some(dirty) = frame.dirty() else {
+            return Ok(());
+        };
+        let columns = dirty.x as usize..(dirty.x + dirty.width) as usize;
+        let end = ((dirty.y + dirty.height) as usize).div_ceil(8);
+        let pages = dirty.y as usize / 8..end.min(self.height as usize / 8);
+        if pages.is_empty() {
+            frame.mark_clean();
+            return Ok(());
+        }
+        self.interface
+            .command(&[
+                0x21,
+                columns.start as u8,
+                columns.end as u8 - 1,
+                0x22,
+                pages.start as u8,
+                pages.end as u8 - 1,
+            ])
+            .await?;
+        let bytes = &frame.as_bytes()[pages.start * 128..pages.end * 128];
+        if columns.len() == 128 {
+            self.interface.data(bytes).await?;
+        } else {
+            for row in bytes.chunks(128) {
+                self.interface.data(&row[columns.clone()]).await?;
+            }
+        }
+        frame.mark_clean();
+        self.refresh = false;
+        Ok(())
+    }
+    /// Set the contrast
+    pub async fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.interface.command(&[0x81, contrast]).await
+    }
+    /// Show filled pixels dark on a bright background
+    pub async fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa6 | inverted as u8]).await
+    }
+    /// Turn the display on or off
+    ///
+    /// The display keeps its contents while it is off and uses very little power.
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xae | on as u8]).await
+    }
+    /// Light up all pixels regardless of the contents, for testing
+    pub async fn set_entire_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa4 | on as u8]).await
+    }
+    /// Rotate the display by 180 degrees in hardware
+    ///
+    /// The whole frame is sent again on the next flush.
+    pub async fn set_flipped(&mut self, flipped: bool) -> Result<(), DI::Error> {
+        let remap = !flipped as u8;
+        self.interface
+            .command(&[0xa0 | remap, 0xc0 | remap << 3])
+            .await?;
+        self.refresh = true;
+        Ok(())
+    }
+}
+
+impl<DI> Ssd1306Controller<DI> {
+    /// Use a display which was already initialized with the given height
+    ///
+    /// No commands are sent. Panics if the height is not 32 or 64.
+    pub fn attach(interface: DI, height: i32) -> Self {
+        assert!(height == 32 || height == 64);
+        Self {
+            height,
+            refresh: true,
+            interface,
+        }
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+impl<DI> Deref for Ssd1306<DI> {
+    type Target = Ssd1306Controller<DI>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<DI> DerefMut for Ssd1306<DI> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<DI> Surface for Ssd1306<DI> {
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        if y < self.height() {
+            self.buffer.pixel(x, y)
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        if y < self.height() {
+            self.buffer.unset(x, y)
+        }
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        if y < self.height() {
+            self.buffer.toggle(x, y)
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        y < self.height() && self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        128
+    }
+    fn height(&self) -> i32 {
+        self.controller.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::SpiInterface;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut display = block_on(Ssd1306::new_128x32(interface)).unwrap();
+        let init = mock.take();
+        assert_eq!(init.len(), 1);
+        assert!(matches!(&init[0], Transfer::Spi(false, bytes) if bytes[4] == 0x1f));
+        display.pixel(127, 31);
+        display.pixel(0, 40);
+        block_on(display.display()).unwrap();
+        let transfers = mock.take();
+        assert_eq!(
+            transfers[0],
+            Transfer::Spi(false, vec![0x21, 0, 127, 0x22, 0, 3])
+        );
+        let Transfer::Spi(true, data) = &transfers[1] else {
+            panic!("expected frame data");
+        };
+        assert_eq!((data.len(), data[511]), (512, 0x80));
+        display.pixel(10, 20);
+        display.pixel(11, 9);
+        block_on(display.display()).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0x21, 10, 11, 0x22, 1, 2]),
+                Transfer::Spi(true, vec![0x00, 0x02]),
+                Transfer::Spi(true, vec![0x10, 0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut controller = block_on(Ssd1306Controller::new_128x32(interface)).unwrap();
+        let mut frame = Framebuffer::new();
+        frame.pixel(3, 3);
+        mock.take();
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = mock.take();
+        assert_eq!(
+            transfers[0],
+            Transfer::Spi(false, vec![0x21, 0, 127, 0x22, 0, 3])
+        );
+        assert!(matches!(&transfers[1], Transfer::Spi(true, data) if data.len() == 512));
+        frame.pixel(0, 40);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(mock.take(), []);
+        assert_eq!(frame.dirty(), None);
+        block_on(controller.set_flipped(true)).unwrap();
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers[0], Transfer::Spi(false, vec![0xa0, 0xc0]));
+        assert_eq!(
+            transfers[1],
+            Transfer::Spi(false, vec![0x21, 0, 127, 0x22, 0, 3])
+        );
+    }
+}
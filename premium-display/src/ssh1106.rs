@@ -1,63 +1,376 @@
+use core::ops::{Deref, DerefMut};
+
 use embedded_hal_async::i2c::I2c;
-use premium_pixel::Surface;
+use premium_pixel::{Framebuffer, Surface};
+
+use crate::oled::{self, PowerUp};
+use crate::{I2cInterface, Interface, pages};
 
 /// SSH1106 OLED Display
-pub struct Ssh1106<I> {
-    buffer: [u8; 1032],
-    i2c: I,
+///
+/// The display owns a framebuffer and can be drawn on directly. All controller commands of
+/// [`Ssh1106Controller`] are available through [`Deref`].
+pub struct Ssh1106<DI> {
+    buffer: Framebuffer<128, 64, 1024>,
+    controller: Ssh1106Controller<DI>,
 }
 
-const ADDRESS: u8 = 0x3c;
+/// SSH1106 OLED controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](Ssh1106Controller::flush), so multiple framebuffers can be used and the interface
+/// can be released between frames.
+pub struct Ssh1106Controller<DI> {
+    config: Ssh1106Config,
+    refresh: bool,
+    interface: DI,
+}
+
+/// Configuration of an [`Ssh1106`] display
+///
+/// The default values work for the common 128x64 modules. The I2C address is set on the
+/// [`I2cInterface`](crate::I2cInterface).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ssh1106Config {
+    /// Number of rows, a multiple of 8 up to 64
+    pub height: u8,
+    /// First column of the panel in the 132 column RAM
+    pub column_offset: u8,
+    /// Contrast
+    pub contrast: u8,
+    /// Pre-charge period
+    pub precharge: u8,
+    /// VCOMH deselect level
+    pub vcomh: u8,
+    /// COM pins hardware configuration
+    pub com_pins: u8,
+    /// Mirror the segments horizontally
+    pub segment_remap: bool,
+    /// Scan the COM outputs in reverse direction
+    pub com_remap: bool,
+    /// Rotate the display by 180 degrees, see [`Ssh1106Controller::set_flipped`]
+    pub flipped: bool,
+}
+
+impl Default for Ssh1106Config {
+    fn default() -> Self {
+        Self {
+            height: 64,
+            column_offset: 0x02,
+            contrast: 0xcf,
+            precharge: 0xf1,
+            vcomh: 0x40,
+            com_pins: 0x12,
+            segment_remap: true,
+            com_remap: true,
+            flipped: false,
+        }
+    }
+}
 
-impl<I: I2c> Ssh1106<I> {
+impl<DI: Interface> Ssh1106<DI> {
     /// Initialize the display
-    pub async fn new(mut i2c: I) -> Result<Self, I::Error> {
-        i2c.write(
-            ADDRESS,
-            &[
-                0x00, 0xae, 0xd5, 0x80, 0xa8, 0x3f, 0xd3, 0x00, 0x40, 0x8d, 0x14, 0x20, 0x00, 0xa1,
-                0xc8, 0xda, 0x12, 0x81, 0xcf, 0xd9, 0xf1, 0xdb, 0x40, 0xa4, 0xa6, 0xaf,
-            ],
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Ssh1106Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the height is not a multiple of 8 between 8 and 64 or the column offset is more
+    /// than 4.
+    pub async fn with_config(interface: DI, config: Ssh1106Config) -> Result<Self, DI::Error> {
+        let controller = Ssh1106Controller::with_config(interface, config).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the current buffer to the display
+    ///
+    /// Only the pages and columns which were changed since the last call are sent.
+    pub async fn display(&mut self) -> Result<(), DI::Error> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<I: I2c> Ssh1106<I2cInterface<I>> {
+    /// Initialize the display on an I2C bus at the default address
+    ///
+    /// Shorthand for `Ssh1106::new(I2cInterface::new(i2c))`.
+    pub async fn new_i2c(i2c: I) -> Result<Self, I::Error> {
+        Self::new(I2cInterface::new(i2c)).await
+    }
+}
+
+impl<DI> Ssh1106<DI> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: Ssh1106Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (Ssh1106Controller<DI>, Framebuffer<128, 64, 1024>) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.controller.release()
+    }
+}
+
+impl<DI: Interface> Ssh1106Controller<DI> {
+    /// Initialize the display
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, Ssh1106Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the height is not a multiple of 8 between 8 and 64 or the column offset is more
+    /// than 4.
+    pub async fn with_config(interface: DI, config: Ssh1106Config) -> Result<Self, DI::Error> {
+        let mut controller = Self::attach(interface, config);
+        let config = controller.config;
+        oled::power_up(
+            &mut controller.interface,
+            PowerUp {
+                height: config.height,
+                com_pins: config.com_pins,
+                contrast: config.contrast,
+                precharge: config.precharge,
+                vcomh: config.vcomh,
+                segment_remap: config.segment_remap ^ config.flipped,
+                com_remap: config.com_remap ^ config.flipped,
+            },
         )
         .await?;
-        let mut display = Self {
-            buffer: [0; 1032],
-            i2c,
-        };
-        display.clear();
-        Ok(display)
+        Ok(controller)
     }
-    /// Send the current buffer to the display
-    pub async fn display(&mut self) -> Result<(), I::Error> {
-        for i in 0..8 {
-            self.i2c
-                .write(ADDRESS, &[0x00, 0xb0 + i as u8, 0x02, 0x10])
-                .await?;
-            self.i2c
-                .write(ADDRESS, &self.buffer[i * 129..(i + 1) * 129])
-                .await?;
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created, attached or
+    /// flipped. Afterwards the framebuffer only knows what changed since it was last flushed, so
+    /// when switching between framebuffers, call [`Framebuffer::mark_dirty`] to send the whole
+    /// frame.
+    pub async fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        if self.refresh {
+            frame.mark_dirty();
         }
+        let mut offset = self.config.column_offset;
+        if self.config.flipped {
+            offset = 132 - 128 - offset;
+        }
+        let height = self.config.height as usize;
+        pages::flush(&mut self.interface, frame, 128, height, |page, column| {
+            let column = offset + column;
+            [0xb0 | page, column & 0x0f, 0x10 | column >> 4]
+        })
+        .await?;
+        self.refresh = false;
+        Ok(())
+    }
+    /// Set the contrast
+    pub async fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.interface.command(&[0x81, contrast]).await
+    }
+    /// Show filled pixels dark on a bright background
+    pub async fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa6 | inverted as u8]).await
+    }
+    /// Turn the display on or off
+    ///
+    /// The display keeps its contents while it is off and uses very little power.
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xae | on as u8]).await
+    }
+    /// Light up all pixels regardless of the contents, for testing
+    pub async fn set_entire_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa4 | on as u8]).await
+    }
+    /// Rotate the display by 180 degrees in hardware
+    ///
+    /// The state is kept in [`Ssh1106Config::flipped`]. The whole frame is sent again on the next
+    /// flush.
+    pub async fn set_flipped(&mut self, flipped: bool) -> Result<(), DI::Error> {
+        let segment = self.config.segment_remap ^ flipped;
+        let com = self.config.com_remap ^ flipped;
+        self.interface
+            .command(&[0xa0 | segment as u8, 0xc0 | (com as u8) << 3])
+            .await?;
+        self.config.flipped = flipped;
+        self.refresh = true;
         Ok(())
     }
 }
 
-impl<I: I2c> Surface for Ssh1106<I> {
-    fn clear(&mut self) {
-        self.buffer.fill(0);
-        for i in 0..8 {
-            self.buffer[i * 129] = 0x40;
+impl<DI> Ssh1106Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    ///
+    /// No commands are sent, so after [`release`](Self::release) the interface can be attached
+    /// again with the [`config`](Self::config) of the old controller. Panics if the height is not
+    /// a multiple of 8 between 8 and 64 or the column offset is more than 4.
+    pub fn attach(interface: DI, config: Ssh1106Config) -> Self {
+        assert!(config.height.is_multiple_of(8) && (8..=64).contains(&config.height));
+        assert!(config.column_offset <= 132 - 128);
+        Self {
+            config,
+            refresh: true,
+            interface,
         }
     }
+    /// Returns the configuration
+    pub fn config(&self) -> &Ssh1106Config {
+        &self.config
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+impl<DI> Deref for Ssh1106<DI> {
+    type Target = Ssh1106Controller<DI>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<DI> DerefMut for Ssh1106<DI> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<DI> Surface for Ssh1106<DI> {
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
     fn pixel(&mut self, x: i32, y: i32) {
-        if (0..self.width()).contains(&x) && (0..self.height()).contains(&y) {
-            let p = (x + (y >> 3) * 128) as usize;
-            self.buffer[p + 1 + p / 128] |= 1 << (y & 7);
+        if y < self.height() {
+            self.buffer.pixel(x, y)
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        if y < self.height() {
+            self.buffer.unset(x, y)
         }
     }
+    fn toggle(&mut self, x: i32, y: i32) {
+        if y < self.height() {
+            self.buffer.toggle(x, y)
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        y < self.height() && self.buffer.get(x, y)
+    }
     fn width(&self) -> i32 {
         128
     }
     fn height(&self) -> i32 {
-        64
+        self.controller.config.height as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::I2cInterface;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    const INIT: [u8; 26] = [
+        0x00, 0xae, 0xd5, 0x80, 0xa8, 0x3f, 0xd3, 0x00, 0x40, 0x8d, 0x14, 0x20, 0x00, 0xa1, 0xc8,
+        0xda, 0x12, 0x81, 0xcf, 0xd9, 0xf1, 0xdb, 0x40, 0xa4, 0xa6, 0xaf,
+    ];
+
+    #[test]
+    fn init() {
+        let mock = Mock::new();
+        block_on(Ssh1106::new(I2cInterface::new(mock.clone()))).unwrap();
+        assert_eq!(mock.take(), [Transfer::I2c(0x3c, INIT.to_vec())]);
+        block_on(Ssh1106::new_i2c(mock.clone())).unwrap();
+        assert_eq!(mock.take(), [Transfer::I2c(0x3c, INIT.to_vec())]);
+        let config = Ssh1106Config {
+            height: 32,
+            com_pins: 0x02,
+            ..Default::default()
+        };
+        block_on(Ssh1106::with_config(
+            I2cInterface::with_address(mock.clone(), 0x3d),
+            config,
+        ))
+        .unwrap();
+        let Transfer::I2c(0x3d, init) = &mock.take()[0] else {
+            panic!("wrong address");
+        };
+        assert_eq!((init[5], init[16]), (0x1f, 0x02));
+    }
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let mut display = block_on(Ssh1106::new(I2cInterface::new(mock.clone()))).unwrap();
+        display.pixel(0, 0);
+        mock.take();
+        block_on(display.display()).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 16);
+        let mut data = Vec::new();
+        for (i, pair) in transfers.chunks(2).enumerate() {
+            assert_eq!(
+                pair[0],
+                Transfer::I2c(0x3c, vec![0x00, 0xb0 + i as u8, 0x02, 0x10])
+            );
+            let Transfer::I2c(0x3c, page) = &pair[1] else {
+                panic!("expected page data");
+            };
+            data.extend_from_slice(page);
+        }
+        assert_eq!(data.len(), 1032);
+        assert!((0..8).all(|i| data[i * 129] == 0x40));
+        assert_eq!((data[1], data[2]), (0x01, 0x00));
+        display.pixel(5, 10);
+        display.pixel(7, 12);
+        block_on(display.display()).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::I2c(0x3c, vec![0x00, 0xb1, 0x07, 0x10]),
+                Transfer::I2c(0x3c, vec![0x40, 0x04, 0x00, 0x10]),
+            ]
+        );
+        block_on(display.display()).unwrap();
+        assert_eq!(mock.take(), []);
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let config = Ssh1106Config {
+            column_offset: 0,
+            ..Default::default()
+        };
+        let interface = I2cInterface::new(mock.clone());
+        let mut controller = block_on(Ssh1106Controller::with_config(interface, config)).unwrap();
+        let mut frame = Framebuffer::new();
+        frame.pixel(0, 0);
+        mock.take();
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(mock.take().len(), 16);
+        block_on(controller.set_flipped(true)).unwrap();
+        let config = *controller.config();
+        assert!(config.flipped);
+        let mut controller = Ssh1106Controller::attach(controller.release(), config);
+        mock.take();
+        frame.pixel(1, 0);
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 16);
+        assert_eq!(
+            transfers[0],
+            Transfer::I2c(0x3c, vec![0x00, 0xb0, 0x04, 0x10])
+        );
     }
 }
@@ -0,0 +1,303 @@
+use core::ops::{Deref, DerefMut};
+
+use premium_pixel::{Framebuffer, Surface};
+
+use crate::{Interface, pages};
+
+/// ST7567 LCD Display, which has the same commands as the ST7565
+pub type St7567<DI> = St7565<DI>;
+
+/// ST7567 LCD controller, which has the same commands as the ST7565
+pub type St7567Controller<DI> = St7565Controller<DI>;
+
+/// ST7565 LCD Display
+///
+/// Supports 128x64 STN panels, usually connected with a [`SpiInterface`](crate::SpiInterface).
+/// The backlight is not controlled by the driver. All controller commands of [`St7565Controller`]
+/// are available through [`Deref`].
+pub struct St7565<DI> {
+    buffer: Framebuffer<128, 64, 1024>,
+    controller: St7565Controller<DI>,
+}
+
+/// ST7565 LCD controller without a framebuffer
+///
+/// Frames are drawn into a separate [`Framebuffer`] and sent with
+/// [`flush`](St7565Controller::flush).
+pub struct St7565Controller<DI> {
+    config: St7565Config,
+    refresh: bool,
+    interface: DI,
+}
+
+/// LCD bias ratio of an [`St7565`] display
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Bias {
+    /// 1/9 bias
+    #[default]
+    OneNinth,
+    /// 1/7 bias
+    OneSeventh,
+}
+
+/// Configuration of an [`St7565`] display
+///
+/// The right values depend on the panel, the defaults work for many common modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct St7565Config {
+    /// LCD bias ratio
+    pub bias: Bias,
+    /// Internal resistor ratio of the voltage regulator, from 0 to 7
+    pub regulator_ratio: u8,
+    /// Electronic volume, which sets the contrast, from 0 to 63
+    pub contrast: u8,
+    /// First column of the panel in the 132 column RAM
+    pub column_offset: u8,
+    /// Mirror the segments horizontally
+    pub segment_remap: bool,
+    /// Scan the COM outputs in reverse direction
+    pub com_remap: bool,
+}
+
+impl Default for St7565Config {
+    fn default() -> Self {
+        Self {
+            bias: Bias::OneNinth,
+            regulator_ratio: 5,
+            contrast: 0x20,
+            column_offset: 0,
+            segment_remap: false,
+            com_remap: true,
+        }
+    }
+}
+
+impl<DI: Interface> St7565<DI> {
+    /// Initialize the display
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, St7565Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the column offset is more than 4.
+    pub async fn with_config(interface: DI, config: St7565Config) -> Result<Self, DI::Error> {
+        let controller = St7565Controller::with_config(interface, config).await?;
+        Ok(Self::from_parts(controller, Framebuffer::new()))
+    }
+    /// Send the current buffer to the display
+    ///
+    /// Only the pages and columns which were changed since the last call are sent.
+    pub async fn display(&mut self) -> Result<(), DI::Error> {
+        self.controller.flush(&mut self.buffer).await
+    }
+}
+
+impl<DI> St7565<DI> {
+    /// Combine a controller and a framebuffer
+    ///
+    /// The whole framebuffer is sent on the next call to [`display`](Self::display).
+    pub fn from_parts(
+        controller: St7565Controller<DI>,
+        buffer: Framebuffer<128, 64, 1024>,
+    ) -> Self {
+        let mut buffer = buffer;
+        buffer.mark_dirty();
+        Self { buffer, controller }
+    }
+    /// Split the display into the controller and the framebuffer
+    pub fn into_parts(self) -> (St7565Controller<DI>, Framebuffer<128, 64, 1024>) {
+        (self.controller, self.buffer)
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.controller.release()
+    }
+}
+
+impl<DI: Interface> St7565Controller<DI> {
+    /// Initialize the display
+    pub async fn new(interface: DI) -> Result<Self, DI::Error> {
+        Self::with_config(interface, St7565Config::default()).await
+    }
+    /// Initialize the display with a custom configuration
+    ///
+    /// Panics if the column offset is more than 4.
+    pub async fn with_config(interface: DI, config: St7565Config) -> Result<Self, DI::Error> {
+        let mut controller = Self::attach(interface, config);
+        controller
+            .interface
+            .command(&[
+                0xe2,
+                0xa2 | (config.bias == Bias::OneSeventh) as u8,
+                0xa0 | config.segment_remap as u8,
+                0xc0 | (config.com_remap as u8) << 3,
+                0x20 | config.regulator_ratio & 0x07,
+                0x81,
+                config.contrast & 0x3f,
+                0x2f,
+                0x40,
+                0xa4,
+                0xa6,
+                0xaf,
+            ])
+            .await?;
+        Ok(controller)
+    }
+    /// Send the changed part of a framebuffer to the display and mark it clean
+    ///
+    /// The whole frame is sent on the first call after the controller was created or attached.
+    /// When switching between framebuffers, call [`Framebuffer::mark_dirty`] to send the whole
+    /// frame.
+    pub async fn flush(&mut self, frame: &mut Framebuffer<128, 64, 1024>) -> Result<(), DI::Error> {
+        if self.refresh {
+            frame.mark_dirty();
+        }
+        let offset = self.config.column_offset;
+        pages::flush(&mut self.interface, frame, 128, 64, |page, column| {
+            let column = offset + column;
+            [0xb0 | page, 0x10 | column >> 4, column & 0x0f]
+        })
+        .await?;
+        self.refresh = false;
+        Ok(())
+    }
+    /// Set the electronic volume, from 0 to 63
+    pub async fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.interface.command(&[0x81, contrast & 0x3f]).await?;
+        self.config.contrast = contrast & 0x3f;
+        Ok(())
+    }
+    /// Show filled pixels bright on a dark background
+    pub async fn set_inverted(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa6 | inverted as u8]).await
+    }
+    /// Turn the display on or off
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xae | on as u8]).await
+    }
+    /// Turn on all pixels regardless of the contents, for testing
+    pub async fn set_entire_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.interface.command(&[0xa4 | on as u8]).await
+    }
+}
+
+impl<DI> St7565Controller<DI> {
+    /// Use a display which was already initialized with the given configuration
+    ///
+    /// No commands are sent. Panics if the column offset is more than 4.
+    pub fn attach(interface: DI, config: St7565Config) -> Self {
+        assert!(config.column_offset <= 132 - 128);
+        Self {
+            config,
+            refresh: true,
+            interface,
+        }
+    }
+    /// Returns the configuration
+    pub fn config(&self) -> &St7565Config {
+        &self.config
+    }
+    /// Returns the interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+impl<DI> Deref for St7565<DI> {
+    type Target = St7565Controller<DI>;
+    fn deref(&self) -> &Self::Target {
+        &self.controller
+    }
+}
+impl<DI> DerefMut for St7565<DI> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.controller
+    }
+}
+
+impl<DI> Surface for St7565<DI> {
+    fn clear(&mut self) {
+        self.buffer.clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.buffer.pixel(x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.buffer.unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.buffer.toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.buffer.get(x, y)
+    }
+    fn width(&self) -> i32 {
+        128
+    }
+    fn height(&self) -> i32 {
+        64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::SpiInterface;
+    use crate::mock::{Mock, Transfer, block_on};
+
+    #[test]
+    fn display() {
+        let mock = Mock::new();
+        let config = St7565Config {
+            bias: Bias::OneSeventh,
+            column_offset: 4,
+            ..Default::default()
+        };
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut display = block_on(St7567::with_config(interface, config)).unwrap();
+        let init = vec![
+            0xe2, 0xa3, 0xa0, 0xc8, 0x25, 0x81, 0x20, 0x2f, 0x40, 0xa4, 0xa6, 0xaf,
+        ];
+        assert_eq!(mock.take(), [Transfer::Spi(false, init)]);
+        block_on(display.display()).unwrap();
+        assert_eq!(mock.take().len(), 16);
+        display.pixel(20, 63);
+        block_on(display.display()).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0xb7, 0x11, 0x08]),
+                Transfer::Spi(true, vec![0x80]),
+            ]
+        );
+    }
+
+    #[test]
+    fn controller() {
+        let mock = Mock::new();
+        let interface = SpiInterface::new(mock.clone(), mock.clone());
+        let mut controller = block_on(St7565Controller::new(interface)).unwrap();
+        block_on(controller.set_contrast(0x50)).unwrap();
+        let config = *controller.config();
+        assert_eq!(config.contrast, 0x10);
+        let mut controller = St7567Controller::attach(controller.release(), config);
+        let mut frame = Framebuffer::new();
+        frame.pixel(0, 0);
+        mock.take();
+        block_on(controller.flush(&mut frame)).unwrap();
+        let transfers = mock.take();
+        assert_eq!(transfers.len(), 16);
+        assert_eq!(transfers[0], Transfer::Spi(false, vec![0xb0, 0x10, 0x00]));
+        frame.pixel(1, 0);
+        block_on(controller.flush(&mut frame)).unwrap();
+        assert_eq!(
+            mock.take(),
+            [
+                Transfer::Spi(false, vec![0xb0, 0x10, 0x01]),
+                Transfer::Spi(true, vec![0x01]),
+            ]
+        );
+    }
+}
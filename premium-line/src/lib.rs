@@ -257,7 +257,7 @@ mod tests {
         let line3 = Line31::from_str(&line2).unwrap();
         assert_eq!(line2, line3);
         assert!(Line15::from_str("b").unwrap() > Line63::from_str("a").unwrap());
-        assert_eq!(Line15::from_str("a").unwrap().as_bytes(), &[b'a']);
+        assert_eq!(Line15::from_str("a").unwrap().as_bytes(), b"a");
     }
 
     #[test]
@@ -1,4 +1,7 @@
-use crate::Surface;
+use crate::{
+    Surface,
+    font::{self, Font, Metrics},
+};
 
 /// Write on a surface using the Awakening typeface
 pub trait Awakening {
@@ -9,25 +12,93 @@ pub trait Awakening {
 
 impl<S: Surface> Awakening for S {
     fn awakening(&mut self, x: i32, y: i32, text: &str) -> i32 {
-        let mut k = 0;
-        let mut w = 0;
-        for glyph in Glyphs::from(text) {
-            let b = glyph.data().next().unwrap_or(0);
-            if (k | (k << 1)) & (b | (b << 1)) != 0 {
-                w += 1;
+        AwakeningFont.draw(self, x, y, text)
+    }
+}
+
+/// The Awakening typeface
+///
+/// Glyphs are only separated by a column of space when they would touch otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwakeningFont;
+
+impl Font for AwakeningFont {
+    fn glyph(&self, c: char) -> Option<font::Glyph<'static>> {
+        let mut buffer = [0; 4];
+        let text = c.encode_utf8(&mut buffer);
+        Glyphs::from(&*text)
+            .next()
+            .filter(|glyph| glyph.text == text.as_bytes())
+            .map(|glyph| font::Glyph::columns(glyph.data, glyph.tall))
+    }
+    fn advance(&self, text: &str) -> i32 {
+        columns(text, |_, _| {})
+    }
+    fn metrics(&self, text: &str) -> Metrics {
+        let mut left = None;
+        let mut right = 0;
+        let mut rows = 0;
+        let advance = columns(text, |w, col| {
+            if col != 0 {
+                left.get_or_insert(w);
+                right = w + 1;
+                rows |= col;
             }
-            for col in glyph.data() {
-                for i in 0..16 {
-                    if col & (1 << i) != 0 {
-                        self.pixel(x + w, y - 10 + i);
-                    }
+        });
+        let mut metrics = Metrics {
+            advance,
+            ascent: self.ascent(),
+            descent: self.descent(),
+            ..Metrics::default()
+        };
+        if let Some(left) = left {
+            metrics.left = left;
+            metrics.right = right;
+            metrics.top = rows.trailing_zeros() as i32 - 10;
+            metrics.bottom = 6 - rows.leading_zeros() as i32;
+        }
+        metrics
+    }
+    fn ascent(&self) -> i32 {
+        8
+    }
+    fn descent(&self) -> i32 {
+        2
+    }
+    fn line_height(&self) -> i32 {
+        11
+    }
+    fn spacing(&self) -> i32 {
+        1
+    }
+    fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> i32 {
+        columns(text, |w, col| {
+            for i in 0..16 {
+                if col & (1 << i) != 0 {
+                    surface.pixel(x + w, y - 10 + i);
                 }
-                k = col;
-                w += 1;
             }
+        })
+    }
+}
+
+/// Walk through all columns of the text.
+/// Returns the width of the text in pixels.
+fn columns(text: &str, mut column: impl FnMut(i32, u16)) -> i32 {
+    let mut k = 0;
+    let mut w = 0;
+    for glyph in Glyphs::from(text) {
+        let b = glyph.data().next().unwrap_or(0);
+        if (k | (k << 1)) & (b | (b << 1)) != 0 {
+            w += 1;
+        }
+        for col in glyph.data() {
+            column(w, col);
+            k = col;
+            w += 1;
         }
-        w
     }
+    w
 }
 
 struct Glyphs<'a> {
@@ -55,6 +126,17 @@ impl<'a> From<&'a str> for Glyphs<'a> {
     }
 }
 
+impl Glyphs<'_> {
+    /// Drop the first character of the text, including all of its UTF-8 continuation bytes.
+    fn skip(&mut self) {
+        let len = 1 + self.text[1..]
+            .iter()
+            .take_while(|&&b| b & 0xc0 == 0x80)
+            .count();
+        self.text = &self.text[len..];
+    }
+}
+
 impl Iterator for Glyphs<'_> {
     type Item = Glyph<'static>;
     fn next(&mut self) -> Option<Self::Item> {
@@ -66,7 +148,7 @@ impl Iterator for Glyphs<'_> {
             }
             let start = INDEX[b as usize >> 2];
             if start < 0 {
-                self.text = &self.text[1..];
+                self.skip();
                 continue 'not_found;
             }
             for glyph in GlyphLookup(&DATA[start as usize..]) {
@@ -74,10 +156,11 @@ impl Iterator for Glyphs<'_> {
                     self.text = &self.text[glyph.text.len()..];
                     return Some(glyph);
                 } else if glyph.text[0] > self.text[0] {
-                    self.text = &self.text[1..];
+                    self.skip();
                     continue 'not_found;
                 }
             }
+            self.skip();
         }
         None
     }
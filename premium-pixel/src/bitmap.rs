@@ -0,0 +1,187 @@
+use crate::{Rect, Surface};
+
+/// A 1-bit image
+///
+/// The pixels are stored in rows from top to bottom. Every row starts with a new byte and the
+/// leftmost pixel is in the most significant bit.
+///
+/// ```
+/// use premium_pixel::{Bitmap, Blit, BlitMode, Framebuffer};
+///
+/// const ARROW: Bitmap = Bitmap::new(5, 3, &[0b00100000, 0b01110000, 0b11111000]);
+///
+/// let mut frame = Framebuffer::<128, 64, 1024>::new();
+/// frame.bitmap(10, 10, &ARROW, BlitMode::Transparent);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bitmap<'a> {
+    data: &'a [u8],
+    stride: usize,
+    x: usize,
+    width: i32,
+    height: i32,
+}
+
+impl<'a> Bitmap<'a> {
+    /// Create a bitmap from packed rows.
+    ///
+    /// Panics if `data` is too short for the given size.
+    pub const fn new(width: i32, height: i32, data: &'a [u8]) -> Self {
+        assert!(width >= 0 && height >= 0);
+        let stride = (width as usize).div_ceil(8);
+        assert!(
+            data.len() >= stride * height as usize,
+            "bitmap data too short"
+        );
+        Self {
+            data,
+            stride,
+            x: 0,
+            width,
+            height,
+        }
+    }
+    /// Returns the width of the bitmap in pixels.
+    pub const fn width(&self) -> i32 {
+        self.width
+    }
+    /// Returns the height of the bitmap in pixels.
+    pub const fn height(&self) -> i32 {
+        self.height
+    }
+    /// Check if a pixel is filled.
+    /// Returns `false` for pixels outside of the bitmap.
+    pub const fn get(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        let bit = self.x + x as usize;
+        self.data[y as usize * self.stride + bit / 8] & (0x80 >> (bit % 8)) != 0
+    }
+    /// Returns a part of the bitmap.
+    ///
+    /// Panics if the rectangle is not completely inside of the bitmap.
+    pub const fn view(&self, rect: Rect) -> Bitmap<'a> {
+        assert!(rect.x >= 0 && rect.y >= 0 && rect.width >= 0 && rect.height >= 0);
+        assert!(rect.x + rect.width <= self.width && rect.y + rect.height <= self.height);
+        let (_, data) = self.data.split_at(rect.y as usize * self.stride);
+        Self {
+            data,
+            stride: self.stride,
+            x: self.x + rect.x as usize,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+/// A bitmap containing a grid of equally sized sprites
+///
+/// The sprites are numbered from left to right and top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteSheet<'a> {
+    bitmap: Bitmap<'a>,
+    width: i32,
+    height: i32,
+}
+
+impl<'a> SpriteSheet<'a> {
+    /// Split a bitmap into sprites of the given size.
+    pub const fn new(bitmap: Bitmap<'a>, width: i32, height: i32) -> Self {
+        assert!(width > 0 && height > 0);
+        Self {
+            bitmap,
+            width,
+            height,
+        }
+    }
+    /// Returns the number of sprites.
+    pub const fn len(&self) -> usize {
+        ((self.bitmap.width / self.width) * (self.bitmap.height / self.height)) as usize
+    }
+    /// Returns `true` if the sheet does not contain a single sprite.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the sprite with the given index.
+    pub const fn get(&self, index: usize) -> Option<Bitmap<'a>> {
+        if index >= self.len() {
+            return None;
+        }
+        let columns = (self.bitmap.width / self.width) as usize;
+        let x = (index % columns) as i32 * self.width;
+        let y = (index / columns) as i32 * self.height;
+        Some(self.bitmap.view(Rect::new(x, y, self.width, self.height)))
+    }
+}
+
+/// How a bitmap is combined with the surface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlitMode {
+    /// Fill the filled pixels of the bitmap, leave the rest untouched
+    #[default]
+    Transparent,
+    /// Fill the filled pixels and clear the empty pixels of the bitmap
+    Opaque,
+    /// Clear the filled pixels and fill the empty pixels of the bitmap
+    Invert,
+    /// Invert the surface where the bitmap is filled
+    Xor,
+}
+
+/// Draw bitmaps on a surface
+pub trait Blit {
+    /// Draw a bitmap with the top left corner at the given position.
+    /// The bitmap is clipped to the surface.
+    fn bitmap(&mut self, x: i32, y: i32, bitmap: &Bitmap<'_>, mode: BlitMode);
+}
+
+impl<S: Surface> Blit for S {
+    fn bitmap(&mut self, x: i32, y: i32, bitmap: &Bitmap<'_>, mode: BlitMode) {
+        let surface = Rect::new(0, 0, self.width(), self.height());
+        let visible = Rect::new(x, y, bitmap.width(), bitmap.height()).intersection(&surface);
+        for j in visible.y..visible.y + visible.height {
+            for i in visible.x..visible.x + visible.width {
+                let filled = bitmap.get(i - x, j - y);
+                match (mode, filled) {
+                    (BlitMode::Transparent | BlitMode::Opaque, true)
+                    | (BlitMode::Invert, false) => self.pixel(i, j),
+                    (BlitMode::Opaque, false) | (BlitMode::Invert, true) => self.unset(i, j),
+                    (BlitMode::Xor, true) => self.toggle(i, j),
+                    (BlitMode::Transparent | BlitMode::Xor, false) => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Framebuffer, Rows};
+
+    const SHEET: SpriteSheet = SpriteSheet::new(
+        Bitmap::new(6, 4, &[0b11000100, 0b11001000, 0b00110000, 0b00110000]),
+        2,
+        2,
+    );
+
+    #[test]
+    fn sprites() {
+        assert_eq!(SHEET.len(), 6);
+        let sprite = SHEET.get(4).unwrap();
+        assert!(sprite.get(0, 0) && sprite.get(1, 1) && !sprite.get(0, 2));
+        assert!(SHEET.get(6).is_none());
+        let mut frame = Framebuffer::<8, 4, 4, Rows>::new();
+        frame.bitmap(-1, -1, &SHEET.get(2).unwrap(), BlitMode::Transparent);
+        assert_eq!(frame.as_bytes(), &[0x00, 0, 0, 0]);
+        frame.bitmap(-1, 0, &SHEET.get(2).unwrap(), BlitMode::Invert);
+        assert_eq!(frame.as_bytes(), &[0x00, 0x80, 0, 0]);
+        frame.bitmap(6, 2, &SHEET.get(0).unwrap(), BlitMode::Xor);
+        frame.bitmap(7, 3, &SHEET.get(0).unwrap(), BlitMode::Xor);
+        assert_eq!(frame.as_bytes(), &[0x00, 0x80, 0x03, 0x02]);
+        frame.bitmap(i32::MAX - 2, 0, &SHEET.get(0).unwrap(), BlitMode::Opaque);
+        frame.bitmap(0, i32::MIN, &SHEET.get(0).unwrap(), BlitMode::Opaque);
+        assert_eq!(frame.as_bytes(), &[0x00, 0x80, 0x03, 0x02]);
+    }
+}
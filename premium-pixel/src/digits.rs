@@ -1,5 +1,8 @@
 pub mod large {
-    use crate::Surface;
+    use crate::{
+        Surface,
+        font::{self, Font, Glyph, Metrics},
+    };
 
     /// Write on a surface using the large digit typeface
     pub trait DigitsLarge {
@@ -10,28 +13,44 @@ pub mod large {
 
     impl<S: Surface> DigitsLarge for S {
         fn digits_large(&mut self, x: i32, y: i32, text: &str) -> i32 {
-            let mut w = 0;
-            for (i, c) in text.chars().enumerate() {
-                if i > 0 {
-                    w += 4;
-                }
-                w += glyph(self, x + w, y, c);
-            }
-            w
+            DigitsLargeFont.draw(self, x, y, text)
         }
     }
 
-    fn glyph<S: Surface>(surface: &mut S, x: i32, y: i32, c: char) -> i32 {
-        let (c, w, o) = lookup(c);
-        let h = c.len() as i32;
-        for (i, l) in c.iter().enumerate() {
-            for j in 0..16 {
-                if l & (1 << (15 - j)) != 0 {
-                    surface.pixel(x + j, y - h + o + i as i32);
-                }
-            }
+    /// The large digit typeface
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct DigitsLargeFont;
+
+    impl Font for DigitsLargeFont {
+        fn glyph(&self, c: char) -> Option<Glyph<'static>> {
+            Some(glyph(c)).filter(|glyph| glyph.advance() >= 0)
+        }
+        fn advance(&self, text: &str) -> i32 {
+            font::advance_spaced(text, 4, glyph)
+        }
+        fn metrics(&self, text: &str) -> Metrics {
+            font::metrics_spaced(self, text, 4, glyph)
+        }
+        fn ascent(&self) -> i32 {
+            24
+        }
+        fn descent(&self) -> i32 {
+            2
         }
-        w
+        fn line_height(&self) -> i32 {
+            30
+        }
+        fn spacing(&self) -> i32 {
+            4
+        }
+        fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> i32 {
+            font::draw_spaced(surface, x, y, text, 4, glyph)
+        }
+    }
+
+    fn glyph(c: char) -> Glyph<'static> {
+        let (c, w, o) = lookup(c);
+        Glyph::rows(c, 0, o - c.len() as i32, w)
     }
 
     fn lookup(c: char) -> (&'static [u16], i32, i32) {
@@ -447,7 +466,10 @@ pub mod large {
 }
 
 pub mod medium {
-    use crate::Surface;
+    use crate::{
+        Surface,
+        font::{self, Font, Glyph, Metrics},
+    };
 
     /// Write on a surface using the medium digit typeface
     pub trait DigitsMedium {
@@ -458,28 +480,44 @@ pub mod medium {
 
     impl<S: Surface> DigitsMedium for S {
         fn digits_medium(&mut self, x: i32, y: i32, text: &str) -> i32 {
-            let mut w = 0;
-            for (i, c) in text.chars().enumerate() {
-                if i > 0 {
-                    w += 2;
-                }
-                w += glyph(self, x + w, y, c);
-            }
-            w
+            DigitsMediumFont.draw(self, x, y, text)
         }
     }
 
-    fn glyph<S: Surface>(surface: &mut S, x: i32, y: i32, c: char) -> i32 {
-        let (c, w, o) = lookup(c);
-        let h = c.len() as i32;
-        for (i, l) in c.iter().enumerate() {
-            for j in 0..16 {
-                if l & (1 << (15 - j)) != 0 {
-                    surface.pixel(x + j, y - h + o + i as i32);
-                }
-            }
+    /// The medium digit typeface
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct DigitsMediumFont;
+
+    impl Font for DigitsMediumFont {
+        fn glyph(&self, c: char) -> Option<Glyph<'static>> {
+            Some(glyph(c)).filter(|glyph| glyph.advance() >= 0)
+        }
+        fn advance(&self, text: &str) -> i32 {
+            font::advance_spaced(text, 2, glyph)
+        }
+        fn metrics(&self, text: &str) -> Metrics {
+            font::metrics_spaced(self, text, 2, glyph)
+        }
+        fn ascent(&self) -> i32 {
+            16
+        }
+        fn descent(&self) -> i32 {
+            2
         }
-        w
+        fn line_height(&self) -> i32 {
+            20
+        }
+        fn spacing(&self) -> i32 {
+            2
+        }
+        fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> i32 {
+            font::draw_spaced(surface, x, y, text, 2, glyph)
+        }
+    }
+
+    fn glyph(c: char) -> Glyph<'static> {
+        let (c, w, o) = lookup(c);
+        Glyph::rows(c, 0, o - c.len() as i32, w)
     }
 
     fn lookup(c: char) -> (&'static [u16], i32, i32) {
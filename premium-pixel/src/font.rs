@@ -0,0 +1,425 @@
+use crate::{Lines, Rect, Surface, wrap};
+
+/// A typeface which can write text on any surface
+///
+/// All positions are relative to the start of the baseline. Code that does layout can take any
+/// font as a parameter.
+///
+/// ```
+/// use premium_pixel::{Font, Surface};
+///
+/// fn title<S: Surface, F: Font>(surface: &mut S, font: &F, text: &str) {
+///     font.draw(surface, 0, font.ascent(), text);
+/// }
+/// ```
+pub trait Font {
+    /// Look up the glyph for a character.
+    /// Returns `None` if the font has no glyph for the character.
+    fn glyph(&self, c: char) -> Option<Glyph<'static>>;
+    /// Returns the width of the text in pixels.
+    fn advance(&self, text: &str) -> i32;
+    /// Measure the text without drawing it.
+    fn metrics(&self, text: &str) -> Metrics;
+    /// Number of pixel rows the tallest glyph reaches above the baseline
+    fn ascent(&self) -> i32;
+    /// Number of pixel rows the lowest glyph reaches below the baseline
+    fn descent(&self) -> i32;
+    /// Distance between the baselines of two lines of text
+    fn line_height(&self) -> i32;
+    /// Horizontal space between two glyphs
+    fn spacing(&self) -> i32;
+    /// Write the text with the baseline starting at the given position.
+    /// Returns the width of the text in pixels.
+    fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> i32;
+    /// Write the text aligned to the given position.
+    /// Returns the box from the ascent to the descent of the written text.
+    ///
+    /// ```
+    /// use premium_pixel::{Align, Anchor, Font, Framebuffer, PremiumFont};
+    ///
+    /// let mut frame = Framebuffer::<128, 64, 1024>::new();
+    /// PremiumFont.draw_aligned(&mut frame, 64, 32, "Centered", Align::Center, Anchor::Middle);
+    /// ```
+    fn draw_aligned<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        x: i32,
+        y: i32,
+        text: &str,
+        align: Align,
+        anchor: Anchor,
+    ) -> Rect {
+        let w = self.advance(text);
+        let x = match align {
+            Align::Left => x,
+            Align::Center => x - w / 2,
+            Align::Right => x - w,
+        };
+        let y = match anchor {
+            Anchor::Top => y + self.ascent(),
+            Anchor::Middle => y + (self.ascent() - self.descent()) / 2,
+            Anchor::Baseline => y,
+            Anchor::Bottom => y - self.descent(),
+        };
+        self.draw(surface, x, y, text);
+        Rect::new(x, y - self.ascent(), w, self.ascent() + self.descent())
+    }
+    /// Returns an iterator over the lines of the text wrapped to the given width.
+    fn lines<'a>(&'a self, text: &'a str, width: i32) -> Lines<'a, Self>
+    where
+        Self: Sized,
+    {
+        Lines::new(self, text, width)
+    }
+    /// Write the text into a box.
+    ///
+    /// The text is broken into lines at `\n` and between words, each line is aligned inside of
+    /// the box. When the text does not fit, the last visible line ends with the `ellipsis` if
+    /// one is given.
+    /// Returns `true` if the text did not fit into the box.
+    fn draw_wrapped<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: Rect,
+        text: &str,
+        align: Align,
+        ellipsis: Option<&str>,
+    ) -> bool
+    where
+        Self: Sized,
+    {
+        wrap::draw_wrapped(self, surface, rect, text, align, ellipsis)
+    }
+}
+
+/// Horizontal alignment of text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    /// The text starts at the position
+    #[default]
+    Left,
+    /// The text is centered on the position
+    Center,
+    /// The text ends at the position
+    Right,
+}
+
+/// Vertical alignment of text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// The ascent of the font is at the position
+    Top,
+    /// The text is centered between ascent and descent
+    Middle,
+    /// The baseline is at the position
+    #[default]
+    Baseline,
+    /// The descent of the font is at the position
+    Bottom,
+}
+
+/// The size of a text
+///
+/// All positions are relative to the start of the baseline, so the ink box of text drawn at
+/// `(x, y)` spans from `(x + left, y + top)` to `(x + right, y + bottom)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Width of the text in pixels
+    pub advance: i32,
+    /// Left edge of the filled pixels
+    pub left: i32,
+    /// Top edge of the filled pixels
+    pub top: i32,
+    /// Right edge of the filled pixels, exclusive
+    pub right: i32,
+    /// Bottom edge of the filled pixels, exclusive
+    pub bottom: i32,
+    /// Number of pixel rows the font reaches above the baseline
+    pub ascent: i32,
+    /// Number of pixel rows the font reaches below the baseline
+    pub descent: i32,
+}
+
+impl Metrics {
+    /// Returns the box around all filled pixels of text drawn at the given position.
+    /// The box is empty if no pixel is filled.
+    pub fn ink(&self, x: i32, y: i32) -> Rect {
+        Rect::new(
+            x + self.left,
+            y + self.top,
+            self.right - self.left,
+            self.bottom - self.top,
+        )
+    }
+    /// Extend the ink box to include another box.
+    fn include(&mut self, left: i32, top: i32, right: i32, bottom: i32) {
+        if self.left == self.right {
+            (self.left, self.top, self.right, self.bottom) = (left, top, right, bottom);
+        } else {
+            self.left = self.left.min(left);
+            self.top = self.top.min(top);
+            self.right = self.right.max(right);
+            self.bottom = self.bottom.max(bottom);
+        }
+    }
+}
+
+/// A single glyph of a font
+#[derive(Debug, Clone, Copy)]
+pub struct Glyph<'a> {
+    data: Data<'a>,
+    advance: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Data<'a> {
+    /// Rows from top to bottom with the leftmost pixel in the most significant bit
+    Rows {
+        rows: &'a [u16],
+        left: i32,
+        top: i32,
+    },
+    /// Columns from left to right with the topmost pixel in the least significant bit.
+    /// Short columns only cover the middle 8 rows of tall columns.
+    Columns { data: &'a [u8], tall: bool },
+}
+
+impl<'a> Glyph<'a> {
+    pub(crate) const fn rows(rows: &'a [u16], left: i32, top: i32, advance: i32) -> Self {
+        Self {
+            data: Data::Rows { rows, left, top },
+            advance,
+        }
+    }
+    pub(crate) const fn columns(data: &'a [u8], tall: bool) -> Self {
+        let advance = if tall { data.len() / 2 } else { data.len() } as i32;
+        Self {
+            data: Data::Columns { data, tall },
+            advance,
+        }
+    }
+    /// Returns the width of the glyph in pixels without the spacing to the next glyph.
+    pub fn advance(&self) -> i32 {
+        self.advance
+    }
+    /// Returns the columns of a glyph stored as columns.
+    fn columns_iter(&self) -> impl DoubleEndedIterator<Item = u16> + ExactSizeIterator + 'a {
+        let (data, tall) = match self.data {
+            Data::Columns { data, tall } => (data, tall),
+            Data::Rows { .. } => (&[][..], false),
+        };
+        let step = if tall { 2 } else { 1 };
+        data.chunks_exact(step).map(move |c| match c {
+            [lo, hi] => u16::from_le_bytes([*lo, *hi]),
+            [b] => u16::from(*b) << 4,
+            _ => 0,
+        })
+    }
+    /// Returns the box around all filled pixels relative to the start of the baseline,
+    /// or `None` if the glyph is empty.
+    pub fn bounds(&self) -> Option<Rect> {
+        let (columns, rows, left, top) = match self.data {
+            Data::Rows { rows, left, top } => {
+                let first = rows.iter().position(|&r| r != 0)?;
+                let last = rows.iter().rposition(|&r| r != 0)?;
+                let columns = rows.iter().fold(0, |a, &r| a | r);
+                let l = columns.leading_zeros() as i32;
+                let r = 16 - columns.trailing_zeros() as i32;
+                (l..r, first as i32..last as i32 + 1, left, top)
+            }
+            Data::Columns { .. } => {
+                let first = self.columns_iter().position(|c| c != 0)?;
+                let last = self.columns_iter().rposition(|c| c != 0)?;
+                let rows = self.columns_iter().fold(0, |a, c| a | c);
+                let t = rows.trailing_zeros() as i32;
+                let b = 16 - rows.leading_zeros() as i32;
+                (first as i32..last as i32 + 1, t..b, 0, -10)
+            }
+        };
+        Some(Rect::new(
+            left + columns.start,
+            top + rows.start,
+            columns.len() as i32,
+            rows.len() as i32,
+        ))
+    }
+    /// Check if a pixel of the glyph is filled.
+    /// The position is relative to the start of the baseline.
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        match self.data {
+            Data::Rows { rows, left, top } => {
+                let (i, j) = (x - left, y - top);
+                (0..16).contains(&i)
+                    && (0..rows.len() as i32).contains(&j)
+                    && rows[j as usize] & (0x8000 >> i) != 0
+            }
+            Data::Columns { .. } => {
+                (0..16).contains(&(y + 10))
+                    && x >= 0
+                    && self
+                        .columns_iter()
+                        .nth(x as usize)
+                        .is_some_and(|col| col & (1 << (y + 10)) != 0)
+            }
+        }
+    }
+    /// Draw the glyph with the baseline starting at the given position.
+    pub fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32) {
+        match self.data {
+            Data::Rows { rows, left, top } => {
+                for (i, l) in rows.iter().enumerate() {
+                    for j in 0..16 {
+                        if l & (1 << (15 - j)) != 0 {
+                            surface.pixel(x + left + j, y + top + i as i32);
+                        }
+                    }
+                }
+            }
+            Data::Columns { .. } => {
+                for (w, col) in self.columns_iter().enumerate() {
+                    for i in 0..16 {
+                        if col & (1 << i) != 0 {
+                            surface.pixel(x + w as i32, y - 10 + i);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write text where all glyphs are separated by the same spacing.
+///
+/// Unknown characters have an advance of `-spacing`, so they take up no space at all.
+pub(crate) fn draw_spaced<S: Surface + ?Sized>(
+    surface: &mut S,
+    x: i32,
+    y: i32,
+    text: &str,
+    spacing: i32,
+    lookup: fn(char) -> Glyph<'static>,
+) -> i32 {
+    let mut w = 0;
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 {
+            w += spacing;
+        }
+        let glyph = lookup(c);
+        glyph.draw(surface, x + w, y);
+        w += glyph.advance();
+    }
+    w
+}
+
+/// Measure text where all glyphs are separated by the same spacing.
+pub(crate) fn metrics_spaced<F: Font>(
+    font: &F,
+    text: &str,
+    spacing: i32,
+    lookup: fn(char) -> Glyph<'static>,
+) -> Metrics {
+    let mut metrics = Metrics {
+        ascent: font.ascent(),
+        descent: font.descent(),
+        ..Metrics::default()
+    };
+    let mut w = 0;
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 {
+            w += spacing;
+        }
+        let glyph = lookup(c);
+        if let Some(b) = glyph.bounds() {
+            metrics.include(w + b.x, b.y, w + b.x + b.width, b.y + b.height);
+        }
+        w += glyph.advance();
+    }
+    metrics.advance = w;
+    metrics
+}
+
+/// Returns the width of text where all glyphs are separated by the same spacing.
+pub(crate) fn advance_spaced(text: &str, spacing: i32, lookup: fn(char) -> Glyph<'static>) -> i32 {
+    let mut w = 0;
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 {
+            w += spacing;
+        }
+        w += lookup(c).advance();
+    }
+    w
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AwakeningFont, DigitsLargeFont, DigitsMediumFont, Measure, PremiumFont};
+
+    /// Collects the box around all drawn pixels
+    #[derive(Default)]
+    struct Ink(Metrics);
+    impl Surface for Ink {
+        fn clear(&mut self) {}
+        fn pixel(&mut self, x: i32, y: i32) {
+            self.0.include(x, y, x + 1, y + 1);
+        }
+        fn unset(&mut self, _x: i32, _y: i32) {}
+        fn get(&self, _x: i32, _y: i32) -> bool {
+            false
+        }
+        fn width(&self) -> i32 {
+            1000
+        }
+        fn height(&self) -> i32 {
+            1000
+        }
+    }
+
+    fn check<F: Font>(font: F, text: &str) {
+        let mut ink = Ink::default();
+        let metrics = font.metrics(text);
+        assert_eq!(font.advance(text), font.draw(&mut Measure, 0, 0, text));
+        assert_eq!(metrics.advance, font.draw(&mut ink, 0, 0, text));
+        assert_eq!(metrics.ink(0, 0), ink.0.ink(0, 0));
+        assert!(-metrics.top <= font.ascent());
+        assert!(metrics.bottom <= font.descent());
+    }
+
+    #[test]
+    fn advance() {
+        check(PremiumFont, "Hello, World! ÄÖÜ 42€ \u{1}");
+        check(AwakeningFont, "Effizienz… «Øre» ff \u{1}");
+        check(DigitsLargeFont, "-12.5°C\t7");
+        check(DigitsMediumFont, "-12.5°C\t7");
+        assert!(PremiumFont.glyph('\u{1}').is_none());
+        assert_eq!(AwakeningFont.glyph('…').map(|g| g.advance()), Some(5));
+        assert_eq!(
+            PremiumFont.draw_aligned(&mut Measure, 50, 20, "AB", Align::Center, Anchor::Top),
+            Rect::new(43, 20, 14, 14)
+        );
+        assert_eq!(
+            AwakeningFont.draw_aligned(&mut Measure, 50, 20, "AB", Align::Right, Anchor::Middle),
+            Rect::new(39, 15, 11, 10)
+        );
+        assert_eq!(
+            PremiumFont.metrics(" "),
+            Metrics {
+                advance: 2,
+                ascent: 11,
+                descent: 3,
+                ..Metrics::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(AwakeningFont.advance("€"), 0);
+        assert_eq!(AwakeningFont.metrics("a☃"), AwakeningFont.metrics("a"));
+        assert_eq!(
+            AwakeningFont.advance("☃a\u{1}b"),
+            AwakeningFont.advance("ab")
+        );
+        check(AwakeningFont, "€☃ a");
+    }
+}
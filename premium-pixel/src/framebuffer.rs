@@ -0,0 +1,276 @@
+use core::marker::PhantomData;
+
+use crate::{Rect, Surface};
+
+mod sealed {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub enum Kind {
+        Pages,
+        Rows,
+        Columns,
+    }
+
+    pub trait Sealed {
+        const KIND: Kind;
+    }
+}
+use sealed::Kind;
+
+/// Memory layout of a [`Framebuffer`]
+///
+/// This trait is sealed and implemented by [`Pages`], [`Rows`] and [`Columns`].
+pub trait Layout: sealed::Sealed {}
+
+/// Pages of 8 rows, each byte holds a vertical strip of 8 pixels with the least significant bit
+/// on top.
+///
+/// This is the layout used by SH1106, SSD1306 and similar display controllers.
+#[derive(Debug, Clone, Copy)]
+pub struct Pages;
+
+/// Rows of pixels, each byte holds a horizontal strip of 8 pixels with the most significant bit
+/// on the left.
+///
+/// This is the layout used by Sharp Memory LCDs and most e-paper controllers.
+#[derive(Debug, Clone, Copy)]
+pub struct Rows;
+
+/// Columns of pixels, each byte holds a vertical strip of 8 pixels with the least significant bit
+/// on top.
+#[derive(Debug, Clone, Copy)]
+pub struct Columns;
+
+impl sealed::Sealed for Pages {
+    const KIND: Kind = Kind::Pages;
+}
+impl sealed::Sealed for Rows {
+    const KIND: Kind = Kind::Rows;
+}
+impl sealed::Sealed for Columns {
+    const KIND: Kind = Kind::Columns;
+}
+impl Layout for Pages {}
+impl Layout for Rows {}
+impl Layout for Columns {}
+
+/// Returns the number of bytes needed to store a `width` x `height` image in the layout `L`.
+pub const fn buffer_size<L: Layout>(width: usize, height: usize) -> usize {
+    match L::KIND {
+        Kind::Pages | Kind::Columns => width * height.div_ceil(8),
+        Kind::Rows => width.div_ceil(8) * height,
+    }
+}
+
+/// An in-memory surface with a width of `W` and a height of `H` pixels
+///
+/// The pixels are stored in `N` bytes using the memory layout `L`. `N` must be equal to
+/// [`buffer_size::<L>(W, H)`](buffer_size), otherwise the framebuffer fails to compile.
+///
+/// The framebuffer keeps track of the area which was changed since it was last marked clean, so
+/// display drivers can send only that part.
+///
+/// ```
+/// use premium_pixel::{Framebuffer, Pages, Premium};
+///
+/// let mut frame = Framebuffer::<128, 64, 1024, Pages>::new();
+/// frame.premium(0, 10, "Hello");
+/// ```
+#[derive(Clone)]
+pub struct Framebuffer<const W: usize, const H: usize, const N: usize, L = Pages> {
+    buffer: [u8; N],
+    dirty: Rect,
+    layout: PhantomData<L>,
+}
+
+impl<const W: usize, const H: usize, const N: usize, L: Layout> Framebuffer<W, H, N, L> {
+    /// Create a new empty framebuffer
+    pub const fn new() -> Self {
+        const { assert!(N == buffer_size::<L>(W, H)) };
+        Self {
+            buffer: [0; N],
+            dirty: Rect::new(0, 0, 0, 0),
+            layout: PhantomData,
+        }
+    }
+    /// Returns the raw bytes of the framebuffer
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.buffer
+    }
+    /// Returns the raw bytes of the framebuffer for modification
+    ///
+    /// This marks the whole framebuffer as dirty.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8; N] {
+        self.mark_dirty();
+        &mut self.buffer
+    }
+    /// Returns the area which was changed since the framebuffer was last marked clean.
+    pub fn dirty(&self) -> Option<Rect> {
+        if self.dirty.width > 0 && self.dirty.height > 0 {
+            Some(self.dirty)
+        } else {
+            None
+        }
+    }
+    /// Mark the whole framebuffer as dirty, for example when the display contents are unknown.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = Rect::new(0, 0, W as i32, H as i32);
+    }
+    /// Mark the framebuffer as clean, usually after it was sent to the display.
+    pub fn mark_clean(&mut self) {
+        self.dirty = Rect::new(0, 0, 0, 0);
+    }
+    fn touch(&mut self, rect: Rect) {
+        let bounds = Rect::new(0, 0, W as i32, H as i32);
+        self.dirty = self.dirty.union(&rect.intersection(&bounds));
+    }
+    fn update(&mut self, x: i32, y: i32, f: impl FnOnce(u8, u8) -> u8) {
+        if (0..self.width()).contains(&x) && (0..self.height()).contains(&y) {
+            let (p, m) = locate::<L>(W, H, x as usize, y as usize);
+            let b = f(self.buffer[p], m);
+            if b != self.buffer[p] {
+                self.buffer[p] = b;
+                self.touch(Rect::new(x, y, 1, 1));
+            }
+        }
+    }
+    /// Draw all filled pixels of the framebuffer onto another surface with the top left corner at
+    /// the given position.
+    pub fn blit<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32) {
+        for j in 0..H {
+            for i in 0..W {
+                let (p, m) = locate::<L>(W, H, i, j);
+                if self.buffer[p] & m != 0 {
+                    surface.pixel(x + i as i32, y + j as i32);
+                }
+            }
+        }
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize, L: Layout> Default
+    for Framebuffer<W, H, N, L>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize, L: Layout> Surface
+    for Framebuffer<W, H, N, L>
+{
+    fn clear(&mut self) {
+        for p in 0..N {
+            if self.buffer[p] != 0 {
+                self.buffer[p] = 0;
+                self.touch(area::<L>(W, H, p));
+            }
+        }
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.update(x, y, |b, m| b | m)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.update(x, y, |b, m| b & !m)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.update(x, y, |b, m| b ^ m)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        if (0..self.width()).contains(&x) && (0..self.height()).contains(&y) {
+            let (p, m) = locate::<L>(W, H, x as usize, y as usize);
+            self.buffer[p] & m != 0
+        } else {
+            false
+        }
+    }
+    fn width(&self) -> i32 {
+        W as i32
+    }
+    fn height(&self) -> i32 {
+        H as i32
+    }
+}
+
+/// Returns the byte offset and the bit mask of a pixel
+fn locate<L: Layout>(width: usize, height: usize, x: usize, y: usize) -> (usize, u8) {
+    match L::KIND {
+        Kind::Pages => ((y >> 3) * width + x, 1 << (y & 7)),
+        Kind::Rows => (y * width.div_ceil(8) + (x >> 3), 0x80 >> (x & 7)),
+        Kind::Columns => (x * height.div_ceil(8) + (y >> 3), 1 << (y & 7)),
+    }
+}
+
+/// Returns the pixels stored in a byte
+fn area<L: Layout>(width: usize, height: usize, p: usize) -> Rect {
+    let (x, y, w, h) = match L::KIND {
+        Kind::Pages => (p % width, p / width * 8, 1, 8),
+        Kind::Rows => (p % width.div_ceil(8) * 8, p / width.div_ceil(8), 8, 1),
+        Kind::Columns => (p / height.div_ceil(8), p % height.div_ceil(8) * 8, 1, 8),
+    };
+    Rect::new(x as i32, y as i32, w, h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Paint;
+
+    #[test]
+    fn layouts() {
+        let mut pages = Framebuffer::<16, 16, 32, Pages>::new();
+        pages.pixel(1, 9);
+        assert_eq!(pages.as_bytes()[17], 0x02);
+        let mut rows = Framebuffer::<12, 3, 6, Rows>::new();
+        rows.pixel(9, 2);
+        assert_eq!(rows.as_bytes()[5], 0x40);
+        let mut columns = Framebuffer::<3, 12, 6, Columns>::new();
+        columns.pixel(2, 9);
+        assert_eq!(columns.as_bytes()[5], 0x02);
+    }
+
+    #[test]
+    fn clipping() {
+        let mut frame = Framebuffer::<8, 8, 8>::new();
+        frame.pixel(-1, 0);
+        frame.pixel(0, 8);
+        frame.pixel(8, 0);
+        assert_eq!(frame.as_bytes(), &[0; 8]);
+        frame.line(-4, -4, 20, 20);
+        let mut copy = Framebuffer::<8, 8, 8, Rows>::new();
+        frame.blit(&mut copy, 0, 0);
+        assert_eq!(copy.as_bytes(), &[0x80, 0x40, 0x20, 0x10, 8, 4, 2, 1]);
+    }
+
+    #[test]
+    fn modes() {
+        let mut frame = Framebuffer::<8, 8, 8>::new();
+        frame.line(0, 0, 7, 0);
+        Paint::unset(&mut frame).line(2, 0, 5, 0);
+        assert_eq!(frame.as_bytes(), &[1, 1, 0, 0, 0, 0, 1, 1]);
+        Paint::toggle(&mut frame).line(0, 0, 0, 7);
+        assert!(!frame.get(0, 0));
+        assert!(frame.get(0, 7));
+        assert!(!frame.get(-1, 7));
+    }
+
+    #[test]
+    fn dirty() {
+        let mut frame = Framebuffer::<16, 16, 32, Pages>::new();
+        assert_eq!(frame.dirty(), None);
+        frame.pixel(3, 4);
+        frame.pixel(9, 2);
+        frame.pixel(20, 20);
+        assert_eq!(frame.dirty(), Some(Rect::new(3, 2, 7, 3)));
+        frame.mark_clean();
+        frame.pixel(3, 4);
+        frame.unset(5, 5);
+        assert_eq!(frame.dirty(), None);
+        frame.clear();
+        assert_eq!(frame.dirty(), Some(Rect::new(3, 0, 7, 8)));
+        let mut rows = Framebuffer::<12, 3, 6, Rows>::new();
+        rows.pixel(9, 2);
+        rows.mark_clean();
+        rows.clear();
+        assert_eq!(rows.dirty(), Some(Rect::new(8, 2, 4, 1)));
+    }
+}
@@ -0,0 +1,200 @@
+use core::convert::Infallible;
+use core::ops::{Deref, DerefMut};
+
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{Framebuffer, Layout, Surface};
+
+/// Use a surface as an embedded-graphics draw target
+///
+/// [`BinaryColor::On`] fills a pixel and [`BinaryColor::Off`] clears it.
+///
+/// ```
+/// use embedded_graphics_core::{Pixel, draw_target::DrawTarget, prelude::*, pixelcolor::BinaryColor};
+/// use premium_pixel::{AsDrawTarget, Framebuffer, Surface};
+///
+/// let mut frame = Framebuffer::<128, 64, 1024>::new();
+/// let mut target = AsDrawTarget::new(&mut frame);
+/// target.draw_iter([Pixel(Point::new(1, 2), BinaryColor::On)]).unwrap();
+/// assert!(frame.get(1, 2));
+/// ```
+#[derive(Debug)]
+pub struct AsDrawTarget<S>(S);
+impl<S> AsDrawTarget<S> {
+    /// Wrap a surface
+    pub fn new(surface: S) -> AsDrawTarget<S> {
+        AsDrawTarget(surface)
+    }
+    /// Returns the wrapped surface
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+impl<S: Surface> OriginDimensions for AsDrawTarget<S> {
+    fn size(&self) -> Size {
+        size(&self.0)
+    }
+}
+impl<S: Surface> DrawTarget for AsDrawTarget<S> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        draw(&mut self.0, pixels);
+        Ok(())
+    }
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        clear(&mut self.0, color);
+        Ok(())
+    }
+}
+impl<S> Deref for AsDrawTarget<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<S> DerefMut for AsDrawTarget<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Use an embedded-graphics draw target as a surface
+///
+/// Errors returned by the draw target are ignored, because drawing on a surface can not fail.
+/// Pixels can not be read back, so [`Surface::get`] always returns `false` and
+/// [`Surface::toggle`] fills pixels instead of inverting them. This also applies to
+/// [`BlitMode::Xor`](crate::BlitMode::Xor). Draw into a [`Framebuffer`](crate::Framebuffer) first
+/// and copy it to the draw target if you need to invert pixels.
+#[derive(Debug)]
+pub struct AsSurface<D>(D);
+impl<D> AsSurface<D> {
+    /// Wrap a draw target
+    pub fn new(target: D) -> AsSurface<D> {
+        AsSurface(target)
+    }
+    /// Returns the wrapped draw target
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+impl<D: DrawTarget<Color = BinaryColor>> AsSurface<D> {
+    fn set(&mut self, x: i32, y: i32, color: BinaryColor) {
+        let origin = self.0.bounding_box().top_left;
+        let _ = self.0.draw_iter([Pixel(origin + Point::new(x, y), color)]);
+    }
+}
+impl<D: DrawTarget<Color = BinaryColor>> Surface for AsSurface<D> {
+    fn clear(&mut self) {
+        let _ = self.0.clear(BinaryColor::Off);
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.set(x, y, BinaryColor::On)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.set(x, y, BinaryColor::Off)
+    }
+    fn get(&self, _x: i32, _y: i32) -> bool {
+        false
+    }
+    fn width(&self) -> i32 {
+        self.0.bounding_box().size.width as i32
+    }
+    fn height(&self) -> i32 {
+        self.0.bounding_box().size.height as i32
+    }
+}
+impl<D> Deref for AsSurface<D> {
+    type Target = D;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<D> DerefMut for AsSurface<D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize, L: Layout> OriginDimensions
+    for Framebuffer<W, H, N, L>
+{
+    fn size(&self) -> Size {
+        size(self)
+    }
+}
+impl<const W: usize, const H: usize, const N: usize, L: Layout> DrawTarget
+    for Framebuffer<W, H, N, L>
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        draw(self, pixels);
+        Ok(())
+    }
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        clear(self, color);
+        Ok(())
+    }
+}
+
+fn size<S: Surface + ?Sized>(surface: &S) -> Size {
+    Size::new(
+        surface.width().max(0) as u32,
+        surface.height().max(0) as u32,
+    )
+}
+
+fn draw<S: Surface + ?Sized>(
+    surface: &mut S,
+    pixels: impl IntoIterator<Item = Pixel<BinaryColor>>,
+) {
+    for Pixel(p, color) in pixels {
+        match color {
+            BinaryColor::On => surface.pixel(p.x, p.y),
+            BinaryColor::Off => surface.unset(p.x, p.y),
+        }
+    }
+}
+
+fn clear<S: Surface + ?Sized>(surface: &mut S, color: BinaryColor) {
+    surface.clear();
+    if color.is_on() {
+        for y in 0..surface.height() {
+            for x in 0..surface.width() {
+                surface.pixel(x, y);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Premium, Rows};
+
+    #[test]
+    fn adapters() {
+        let mut frame = Framebuffer::<16, 2, 4, Rows>::new();
+        let mut surface = AsSurface::new(AsDrawTarget::new(&mut frame));
+        assert_eq!((surface.width(), surface.height()), (16, 2));
+        surface.pixel(0, 0);
+        surface.pixel(9, 1);
+        surface.unset(0, 0);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0x40]);
+        DrawTarget::clear(&mut frame, BinaryColor::On).unwrap();
+        assert_eq!(frame.as_bytes(), &[0xff; 4]);
+        let mut frame = Framebuffer::<128, 16, 256, Rows>::new();
+        let width = AsSurface::new(&mut frame).premium(0, 12, "Hi");
+        assert!(width > 0 && frame.as_bytes().iter().any(|&b| b != 0));
+    }
+}
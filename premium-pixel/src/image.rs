@@ -0,0 +1,483 @@
+use core::{error, fmt};
+
+use crate::Bitmap;
+
+/// Error type for parsing and decoding image files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageError {
+    /// The data is not in one of the supported formats
+    UnknownFormat,
+    /// The file is malformed or truncated
+    Invalid,
+    /// The file uses a feature which is not supported, like compression or colors
+    Unsupported,
+    /// The buffer is too small for the decoded image
+    NoSpace,
+}
+
+/// Supported image file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Netpbm bitmap, either plain (`P1`) or raw (`P4`)
+    Pbm,
+    /// X11 bitmap
+    Xbm,
+    /// Uncompressed Windows bitmap with 1 bit per pixel
+    Bmp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    PbmPlain,
+    PbmRaw,
+    Xbm,
+    Bmp { bottom_up: bool, invert: bool },
+}
+
+/// A 1-bit image file
+///
+/// The image can be decoded into a [`Bitmap`] which can then be drawn on any surface. Black
+/// pixels, or the darker color for BMP files, become filled pixels. All methods are `const`, so
+/// images can also be decoded at compile time with the [`bitmap!`](crate::bitmap!) macro.
+///
+/// ```
+/// use premium_pixel::{Blit, BlitMode, Framebuffer, Image};
+///
+/// let image = Image::parse(b"P1\n3 2\n0 1 0\n1 1 1\n").unwrap();
+/// let mut buffer = [0; 2];
+/// let bitmap = image.decode(&mut buffer).unwrap();
+/// let mut frame = Framebuffer::<128, 64, 1024>::new();
+/// frame.bitmap(0, 0, &bitmap, BlitMode::Transparent);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Image<'a> {
+    data: &'a [u8],
+    kind: Kind,
+    offset: usize,
+    width: i32,
+    height: i32,
+}
+
+impl<'a> Image<'a> {
+    /// Parse the header of an image file.
+    /// The format is detected automatically.
+    pub const fn parse(data: &'a [u8]) -> Result<Self, ImageError> {
+        if starts_with(data, 0, b"P1") || starts_with(data, 0, b"P4") {
+            parse_pbm(data)
+        } else if starts_with(data, 0, b"BM") {
+            parse_bmp(data)
+        } else if starts_with(data, skip(data, 0, false), b"#define") {
+            parse_xbm(data)
+        } else {
+            Err(ImageError::UnknownFormat)
+        }
+    }
+    /// Returns the format of the file.
+    pub const fn format(&self) -> Format {
+        match self.kind {
+            Kind::PbmPlain | Kind::PbmRaw => Format::Pbm,
+            Kind::Xbm => Format::Xbm,
+            Kind::Bmp { .. } => Format::Bmp,
+        }
+    }
+    /// Returns the width of the image in pixels.
+    pub const fn width(&self) -> i32 {
+        self.width
+    }
+    /// Returns the height of the image in pixels.
+    pub const fn height(&self) -> i32 {
+        self.height
+    }
+    /// Returns the number of bytes needed to decode the image.
+    pub const fn size(&self) -> usize {
+        self.stride() * self.height as usize
+    }
+    const fn stride(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+    /// Decode the image into the buffer.
+    /// Returns a bitmap of the image which borrows the buffer.
+    pub const fn decode<'b>(&self, buffer: &'b mut [u8]) -> Result<Bitmap<'b>, ImageError> {
+        let size = self.size();
+        if buffer.len() < size {
+            return Err(ImageError::NoSpace);
+        }
+        let result = match self.kind {
+            Kind::PbmPlain => self.decode_plain(buffer),
+            Kind::PbmRaw => self.decode_rows(buffer, false, false, size),
+            Kind::Xbm => self.decode_xbm(buffer),
+            Kind::Bmp { bottom_up, invert } => {
+                let stride = self.stride().div_ceil(4) * 4;
+                self.decode_rows(buffer, bottom_up, invert, stride * self.height as usize)
+            }
+        };
+        if let Err(e) = result {
+            return Err(e);
+        }
+        let buffer: &'b [u8] = buffer;
+        Ok(Bitmap::new(self.width, self.height, buffer))
+    }
+    /// Decode the image into an array of exactly [`size`](Image::size) bytes.
+    ///
+    /// Panics if the size does not match or the image is invalid. This is meant to be used at
+    /// compile time through the [`bitmap!`](crate::bitmap!) macro.
+    pub const fn to_array<const N: usize>(&self) -> [u8; N] {
+        assert!(N == self.size(), "array size does not match the image");
+        let mut array = [0; N];
+        if self.decode(&mut array).is_err() {
+            panic!("invalid image data");
+        }
+        array
+    }
+
+    /// Copy rows of packed pixels, `rows` is the number of bytes used by the rows in the file.
+    const fn decode_rows(
+        &self,
+        buffer: &mut [u8],
+        bottom_up: bool,
+        invert: bool,
+        rows: usize,
+    ) -> Result<(), ImageError> {
+        if self.data.len() < self.offset + rows {
+            return Err(ImageError::Invalid);
+        }
+        let stride = self.stride();
+        let source = if self.height > 0 {
+            rows / self.height as usize
+        } else {
+            0
+        };
+        let mut y = 0;
+        while y < self.height as usize {
+            let row = if bottom_up {
+                self.height as usize - 1 - y
+            } else {
+                y
+            };
+            let mut i = 0;
+            while i < stride {
+                let b = self.data[self.offset + row * source + i];
+                buffer[y * stride + i] = if invert { !b } else { b };
+                i += 1;
+            }
+            y += 1;
+        }
+        Ok(())
+    }
+
+    const fn decode_plain(&self, buffer: &mut [u8]) -> Result<(), ImageError> {
+        let stride = self.stride();
+        let mut p = self.offset;
+        let mut y = 0;
+        while y < self.height as usize {
+            let mut x = 0;
+            while x < self.width as usize {
+                if x % 8 == 0 {
+                    buffer[y * stride + x / 8] = 0;
+                }
+                p = skip(self.data, p, true);
+                if p >= self.data.len() {
+                    return Err(ImageError::Invalid);
+                }
+                match self.data[p] {
+                    b'0' => {}
+                    b'1' => buffer[y * stride + x / 8] |= 0x80 >> (x % 8),
+                    _ => return Err(ImageError::Invalid),
+                }
+                p += 1;
+                x += 1;
+            }
+            y += 1;
+        }
+        Ok(())
+    }
+
+    const fn decode_xbm(&self, buffer: &mut [u8]) -> Result<(), ImageError> {
+        let mut p = self.offset;
+        let mut i = 0;
+        while i < self.size() {
+            while p < self.data.len() && (is_space(self.data[p]) || self.data[p] == b',') {
+                p += 1;
+            }
+            let (n, next) = if starts_with(self.data, p, b"0x") || starts_with(self.data, p, b"0X")
+            {
+                match number(self.data, p + 2, 16) {
+                    Ok(n) => n,
+                    Err(e) => return Err(e),
+                }
+            } else {
+                match number(self.data, p, 10) {
+                    Ok(n) => n,
+                    Err(e) => return Err(e),
+                }
+            };
+            if n > 255 {
+                return Err(ImageError::Invalid);
+            }
+            buffer[i] = (n as u8).reverse_bits();
+            p = next;
+            i += 1;
+        }
+        Ok(())
+    }
+}
+
+const fn parse_pbm(data: &[u8]) -> Result<Image<'_>, ImageError> {
+    let (width, p) = match number(data, skip(data, 2, true), 10) {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    let (height, mut p) = match number(data, skip(data, p, true), 10) {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    if p >= data.len() || !is_space(data[p]) {
+        return Err(ImageError::Invalid);
+    }
+    let kind = if data[1] == b'1' {
+        Kind::PbmPlain
+    } else {
+        p += 1;
+        Kind::PbmRaw
+    };
+    image(data, kind, p, width, height)
+}
+
+const fn parse_xbm(data: &[u8]) -> Result<Image<'_>, ImageError> {
+    let (width, p) = match define(data, b"_width") {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    let (height, _) = match define(data, b"_height") {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    match find(data, p, b"{") {
+        Some(p) => image(data, Kind::Xbm, p + 1, width, height),
+        None => Err(ImageError::Invalid),
+    }
+}
+
+const fn parse_bmp(data: &[u8]) -> Result<Image<'_>, ImageError> {
+    if data.len() < 54 {
+        return Err(ImageError::Invalid);
+    }
+    let offset = le(data, 10, 4) as usize;
+    let header = le(data, 14, 4) as usize;
+    if header < 40 || le(data, 26, 2) != 1 {
+        return Err(ImageError::Unsupported);
+    }
+    if le(data, 28, 2) != 1 || le(data, 30, 4) != 0 {
+        return Err(ImageError::Unsupported);
+    }
+    let palette = 14 + header;
+    if data.len() < palette + 8 {
+        return Err(ImageError::Invalid);
+    }
+    let invert = luma(data, palette) < luma(data, palette + 4);
+    let width = le(data, 18, 4) as i32;
+    let height = le(data, 22, 4) as i32;
+    if width < 0 || height == i32::MIN {
+        return Err(ImageError::Invalid);
+    }
+    let kind = Kind::Bmp {
+        bottom_up: height > 0,
+        invert,
+    };
+    image(
+        data,
+        kind,
+        offset,
+        width as usize,
+        height.unsigned_abs() as usize,
+    )
+}
+
+const fn image(
+    data: &[u8],
+    kind: Kind,
+    offset: usize,
+    width: usize,
+    height: usize,
+) -> Result<Image<'_>, ImageError> {
+    if width > 0x7fff || height > 0x7fff {
+        return Err(ImageError::Unsupported);
+    }
+    Ok(Image {
+        data,
+        kind,
+        offset,
+        width: width as i32,
+        height: height as i32,
+    })
+}
+
+/// Brightness of a BGR palette entry.
+const fn luma(data: &[u8], p: usize) -> u32 {
+    data[p] as u32 + 6 * data[p + 1] as u32 + 3 * data[p + 2] as u32
+}
+
+const fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+/// Skip whitespace and optionally `#` comments.
+const fn skip(data: &[u8], mut p: usize, comments: bool) -> usize {
+    while p < data.len() {
+        if is_space(data[p]) {
+            p += 1;
+        } else if comments && data[p] == b'#' {
+            while p < data.len() && data[p] != b'\n' {
+                p += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    p
+}
+
+const fn starts_with(data: &[u8], p: usize, prefix: &[u8]) -> bool {
+    if data.len() < p + prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if data[p + i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn find(data: &[u8], mut p: usize, needle: &[u8]) -> Option<usize> {
+    while p + needle.len() <= data.len() {
+        if starts_with(data, p, needle) {
+            return Some(p);
+        }
+        p += 1;
+    }
+    None
+}
+
+/// Parse a number, returns the number and the position after it.
+const fn number(data: &[u8], mut p: usize, radix: u32) -> Result<(usize, usize), ImageError> {
+    let start = p;
+    let mut n: usize = 0;
+    while p < data.len() {
+        let Some(d) = (data[p] as char).to_digit(radix) else {
+            break;
+        };
+        n = match n.checked_mul(radix as usize) {
+            Some(n) => n + d as usize,
+            None => return Err(ImageError::Invalid),
+        };
+        p += 1;
+    }
+    if p == start {
+        Err(ImageError::Invalid)
+    } else {
+        Ok((n, p))
+    }
+}
+
+/// Find the value of a `#define` ending in `suffix`.
+const fn define(data: &[u8], suffix: &[u8]) -> Result<(usize, usize), ImageError> {
+    match find(data, 0, suffix) {
+        Some(p) => number(data, skip(data, p + suffix.len(), false), 10),
+        None => Err(ImageError::Invalid),
+    }
+}
+
+/// Read a little endian number with `n` bytes.
+const fn le(data: &[u8], p: usize, n: usize) -> u32 {
+    let mut v = 0;
+    let mut i = n;
+    while i > 0 {
+        i -= 1;
+        v = v << 8 | data[p + i] as u32;
+    }
+    v
+}
+
+/// Embed an image file as a [`Bitmap`](crate::Bitmap).
+///
+/// The data is parsed and decoded at compile time, so an invalid image fails the build.
+///
+/// ```
+/// use premium_pixel::{Bitmap, bitmap};
+///
+/// const DOT: Bitmap = bitmap!(b"P1 3 3 0 1 0 1 1 1 0 1 0");
+/// // const LOGO: Bitmap = bitmap!(include_bytes!("logo.pbm"));
+/// assert!(DOT.get(1, 1));
+/// ```
+#[macro_export]
+macro_rules! bitmap {
+    ($data:expr) => {{
+        const IMAGE: $crate::Image<'static> = match $crate::Image::parse($data) {
+            Ok(image) => image,
+            Err(_) => panic!("invalid image file"),
+        };
+        const DATA: [u8; IMAGE.size()] = IMAGE.to_array();
+        $crate::Bitmap::new(IMAGE.width(), IMAGE.height(), &DATA)
+    }};
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => f.write_str("unknown image format"),
+            Self::Invalid => f.write_str("invalid image data"),
+            Self::Unsupported => f.write_str("unsupported image format"),
+            Self::NoSpace => f.write_str("not enough space for image"),
+        }
+    }
+}
+impl error::Error for ImageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARROW: Bitmap = crate::bitmap!(b"P4\n# arrow\n9 2\n\x08\x00\xff\x80");
+
+    fn check(data: &[u8], format: Format) {
+        let image = Image::parse(data).unwrap();
+        assert_eq!(image.format(), format);
+        assert_eq!((image.width(), image.height()), (9, 2));
+        let mut buffer = [0xaa; 4];
+        assert_eq!(image.decode(&mut buffer).unwrap(), ARROW);
+        assert_eq!(buffer, [0x08, 0x00, 0xff, 0x80]);
+    }
+
+    #[test]
+    fn formats() {
+        check(b"P1 9 2 000010000 11111 1111", Format::Pbm);
+        check(
+            b"#define arrow_width 9\n#define arrow_height 2\n\
+              static unsigned char arrow_bits[] = {\n  0x10, 0x00, 0xff, 0x01 };\n",
+            Format::Xbm,
+        );
+        let mut bmp = [0; 70];
+        bmp[..2].copy_from_slice(b"BM");
+        bmp[10] = 62;
+        bmp[14] = 40;
+        bmp[18] = 9;
+        bmp[22] = 2;
+        bmp[26] = 1;
+        bmp[28] = 1;
+        bmp[58..62].copy_from_slice(&[0xff, 0xff, 0xff, 0]);
+        bmp[62..70].copy_from_slice(&[0x00, 0x7f, 0, 0, 0xf7, 0xff, 0, 0]);
+        check(&bmp, Format::Bmp);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Image::parse(b"GIF89a"), Err(ImageError::UnknownFormat));
+        assert_eq!(Image::parse(b"P1 9"), Err(ImageError::Invalid));
+        let image = Image::parse(b"P1 2 2 0 1 1").unwrap();
+        assert_eq!(image.decode(&mut [0; 2]), Err(ImageError::Invalid));
+        assert_eq!(image.decode(&mut [0; 1]), Err(ImageError::NoSpace));
+    }
+}
@@ -4,15 +4,31 @@
 #![warn(missing_docs)]
 
 mod awakening;
+mod bitmap;
 mod digits;
+mod font;
+mod framebuffer;
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+mod image;
 mod premium;
+mod shapes;
 mod utils;
+mod wrap;
 
-pub use awakening::Awakening;
-pub use digits::large::DigitsLarge;
-pub use digits::medium::DigitsMedium;
-pub use premium::Premium;
+pub use awakening::{Awakening, AwakeningFont};
+pub use bitmap::{Bitmap, Blit, BlitMode, SpriteSheet};
+pub use digits::large::{DigitsLarge, DigitsLargeFont};
+pub use digits::medium::{DigitsMedium, DigitsMediumFont};
+pub use font::{Align, Anchor, Font, Glyph, Metrics};
+pub use framebuffer::{Columns, Framebuffer, Layout, Pages, Rows, buffer_size};
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::{AsDrawTarget, AsSurface};
+pub use image::{Format, Image, ImageError};
+pub use premium::{Premium, PremiumFont};
+pub use shapes::Shapes;
 pub use utils::*;
+pub use wrap::Lines;
 
 /// A drawable surface
 pub trait Surface {
@@ -20,6 +36,27 @@ pub trait Surface {
     fn clear(&mut self);
     /// Fill a pixel
     fn pixel(&mut self, x: i32, y: i32);
+    /// Clear a pixel
+    ///
+    /// Erasing with [`Paint`], clearing a [`Clip`] or [`Viewport`] and opaque blitting rely on
+    /// this.
+    fn unset(&mut self, x: i32, y: i32);
+    /// Invert a pixel
+    ///
+    /// The default implementation reads the pixel with [`get`](Surface::get) and then fills or
+    /// clears it.
+    fn toggle(&mut self, x: i32, y: i32) {
+        if self.get(x, y) {
+            self.unset(x, y)
+        } else {
+            self.pixel(x, y)
+        }
+    }
+    /// Check if a pixel is filled
+    ///
+    /// Surfaces which can not be read back return `false`, which makes
+    /// [`toggle`](Surface::toggle) fill pixels instead of inverting them.
+    fn get(&self, x: i32, y: i32) -> bool;
     /// Get the width of the surface
     fn width(&self) -> i32;
     /// Get the height of the surface
@@ -50,3 +87,30 @@ pub trait Surface {
         }
     }
 }
+
+impl<S: Surface + ?Sized> Surface for &mut S {
+    fn clear(&mut self) {
+        (**self).clear()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        (**self).pixel(x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        (**self).unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        (**self).toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        (**self).get(x, y)
+    }
+    fn width(&self) -> i32 {
+        (**self).width()
+    }
+    fn height(&self) -> i32 {
+        (**self).height()
+    }
+    fn line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        (**self).line(x1, y1, x2, y2)
+    }
+}
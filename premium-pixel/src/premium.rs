@@ -1,4 +1,7 @@
-use crate::Surface;
+use crate::{
+    Surface,
+    font::{self, Font, Glyph, Metrics},
+};
 
 /// Write on a surface using the Premium typeface
 pub trait Premium {
@@ -9,28 +12,44 @@ pub trait Premium {
 
 impl<S: Surface> Premium for S {
     fn premium(&mut self, x: i32, y: i32, text: &str) -> i32 {
-        let mut w = 0;
-        for (i, c) in text.chars().enumerate() {
-            if i > 0 {
-                w += 2;
-            }
-            w += glyph(self, x + w, y, c);
-        }
-        w
+        PremiumFont.draw(self, x, y, text)
     }
 }
 
-fn glyph<S: Surface>(surface: &mut S, x: i32, y: i32, c: char) -> i32 {
-    let (c, w, o) = lookup(c);
-    let h = c.len() as i32;
-    for (i, l) in c.iter().enumerate() {
-        for j in 0..16 {
-            if l & (1 << (15 - j)) != 0 {
-                surface.pixel(x + j - 2, y - h + o + i as i32);
-            }
-        }
+/// The Premium typeface
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PremiumFont;
+
+impl Font for PremiumFont {
+    fn glyph(&self, c: char) -> Option<Glyph<'static>> {
+        Some(glyph(c)).filter(|glyph| glyph.advance() >= 0)
+    }
+    fn advance(&self, text: &str) -> i32 {
+        font::advance_spaced(text, 2, glyph)
+    }
+    fn metrics(&self, text: &str) -> Metrics {
+        font::metrics_spaced(self, text, 2, glyph)
+    }
+    fn ascent(&self) -> i32 {
+        11
+    }
+    fn descent(&self) -> i32 {
+        3
     }
-    w
+    fn line_height(&self) -> i32 {
+        16
+    }
+    fn spacing(&self) -> i32 {
+        2
+    }
+    fn draw<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> i32 {
+        font::draw_spaced(surface, x, y, text, 2, glyph)
+    }
+}
+
+fn glyph(c: char) -> Glyph<'static> {
+    let (c, w, o) = lookup(c);
+    Glyph::rows(c, -2, o - c.len() as i32, w)
 }
 
 fn lookup(c: char) -> (&'static [u16], i32, i32) {
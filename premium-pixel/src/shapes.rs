@@ -0,0 +1,484 @@
+use crate::Surface;
+
+/// Draw primitive shapes on a surface
+///
+/// All shapes are clipped to the surface, so they can be partially or completely outside of it.
+pub trait Shapes {
+    /// Draw a horizontal line from `x1` to `x2`
+    fn hline(&mut self, x1: i32, x2: i32, y: i32);
+    /// Draw a vertical line from `y1` to `y2`
+    fn vline(&mut self, x: i32, y1: i32, y2: i32);
+    /// Draw the outline of a rectangle with the top left corner at the given position
+    fn rect(&mut self, x: i32, y: i32, width: i32, height: i32);
+    /// Fill a rectangle with the top left corner at the given position
+    fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32);
+    /// Draw the outline of a rectangle with rounded corners of radius `r`
+    fn rounded_rect(&mut self, x: i32, y: i32, width: i32, height: i32, r: i32);
+    /// Fill a rectangle with rounded corners of radius `r`
+    fn fill_rounded_rect(&mut self, x: i32, y: i32, width: i32, height: i32, r: i32);
+    /// Draw the outline of a circle around the given center
+    fn circle(&mut self, x: i32, y: i32, r: i32);
+    /// Fill a circle around the given center
+    fn fill_circle(&mut self, x: i32, y: i32, r: i32);
+    /// Draw the outline of an ellipse around the given center
+    fn ellipse(&mut self, x: i32, y: i32, rx: i32, ry: i32);
+    /// Fill an ellipse around the given center
+    fn fill_ellipse(&mut self, x: i32, y: i32, rx: i32, ry: i32);
+    /// Draw a circular arc around the given center.
+    ///
+    /// The angles are in degrees, clockwise, starting at 3 o'clock. The arc is drawn clockwise
+    /// from `start` to `end`.
+    fn arc(&mut self, x: i32, y: i32, r: i32, start: i32, end: i32);
+    /// Fill a circular sector around the given center.
+    ///
+    /// The angles are the same as for [`arc`](Shapes::arc).
+    fn fill_arc(&mut self, x: i32, y: i32, r: i32, start: i32, end: i32);
+    /// Draw the outline of a triangle
+    fn triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32);
+    /// Fill a triangle including its outline
+    fn fill_triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32);
+    /// Draw the outline of a closed polygon
+    fn polygon(&mut self, points: &[(i32, i32)]);
+    /// Fill a polygon using the even-odd rule.
+    ///
+    /// A pixel is filled when its center lies inside the polygon, where the pixel `(x, y)` spans
+    /// from `(x, y)` to `(x + 1, y + 1)`. So the polygon `(0, 0), (4, 0), (4, 4), (0, 4)` fills
+    /// the same 4 by 4 pixels as `fill_rect(0, 0, 4, 4)`.
+    fn fill_polygon(&mut self, points: &[(i32, i32)]);
+}
+
+impl<S: Surface> Shapes for S {
+    fn hline(&mut self, x1: i32, x2: i32, y: i32) {
+        if (0..self.height()).contains(&y) {
+            for x in x1.min(x2).max(0)..=x1.max(x2).min(self.width() - 1) {
+                self.pixel(x, y);
+            }
+        }
+    }
+    fn vline(&mut self, x: i32, y1: i32, y2: i32) {
+        if (0..self.width()).contains(&x) {
+            for y in y1.min(y2).max(0)..=y1.max(y2).min(self.height() - 1) {
+                self.pixel(x, y);
+            }
+        }
+    }
+    fn rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (x2, y2) = (x.saturating_add(width - 1), y.saturating_add(height - 1));
+        self.hline(x, x2, y);
+        if height > 1 {
+            self.hline(x, x2, y2);
+        }
+        if height > 2 {
+            self.vline(x, y.saturating_add(1), y2 - 1);
+            if width > 1 {
+                self.vline(x2, y.saturating_add(1), y2 - 1);
+            }
+        }
+    }
+    fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            for j in y.max(0)..y.saturating_add(height).min(self.height()) {
+                self.hline(x, x.saturating_add(width - 1), j);
+            }
+        }
+    }
+    fn rounded_rect(&mut self, x: i32, y: i32, width: i32, height: i32, r: i32) {
+        let span = rounded_span(x, y, width, height, r);
+        let bottom = y as i64 + height as i64 - 1;
+        spans(self, y as i64, bottom, false, span, Self::hline);
+    }
+    fn fill_rounded_rect(&mut self, x: i32, y: i32, width: i32, height: i32, r: i32) {
+        let span = rounded_span(x, y, width, height, r);
+        let bottom = y as i64 + height as i64 - 1;
+        spans(self, y as i64, bottom, true, span, Self::hline);
+    }
+    fn circle(&mut self, x: i32, y: i32, r: i32) {
+        self.ellipse(x, y, r, r);
+    }
+    fn fill_circle(&mut self, x: i32, y: i32, r: i32) {
+        self.fill_ellipse(x, y, r, r);
+    }
+    fn ellipse(&mut self, x: i32, y: i32, rx: i32, ry: i32) {
+        let span = ellipse_span(x, y, rx, ry);
+        let (top, bottom) = (y as i64 - ry as i64, y as i64 + ry as i64);
+        spans(self, top, bottom, false, span, Self::hline);
+    }
+    fn fill_ellipse(&mut self, x: i32, y: i32, rx: i32, ry: i32) {
+        let span = ellipse_span(x, y, rx, ry);
+        let (top, bottom) = (y as i64 - ry as i64, y as i64 + ry as i64);
+        spans(self, top, bottom, true, span, Self::hline);
+    }
+    fn arc(&mut self, x: i32, y: i32, r: i32, start: i32, end: i32) {
+        let span = ellipse_span(x, y, r, r);
+        let sector = Sector::new(start, end);
+        let (top, bottom) = (y as i64 - r as i64, y as i64 + r as i64);
+        spans(self, top, bottom, false, span, |s, x1, x2, j| {
+            for i in x1..=x2 {
+                if sector.contains(i as i64 - x as i64, j as i64 - y as i64) {
+                    s.pixel(i, j);
+                }
+            }
+        });
+    }
+    fn fill_arc(&mut self, x: i32, y: i32, r: i32, start: i32, end: i32) {
+        let span = ellipse_span(x, y, r, r);
+        let sector = Sector::new(start, end);
+        let (top, bottom) = (y as i64 - r as i64, y as i64 + r as i64);
+        spans(self, top, bottom, true, span, |s, x1, x2, j| {
+            for i in x1..=x2 {
+                if sector.contains(i as i64 - x as i64, j as i64 - y as i64) {
+                    s.pixel(i, j);
+                }
+            }
+        });
+    }
+    fn triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32) {
+        let points = [(x1, y1), (x2, y2), (x3, y3)];
+        let (top, bottom) = (y1.min(y2).min(y3), y1.max(y2).max(y3));
+        let span = convex_span(&points);
+        spans(self, top as i64, bottom as i64, false, span, Self::hline);
+    }
+    fn fill_triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32) {
+        let points = [(x1, y1), (x2, y2), (x3, y3)];
+        let (top, bottom) = (y1.min(y2).min(y3), y1.max(y2).max(y3));
+        let span = convex_span(&points);
+        spans(self, top as i64, bottom as i64, true, span, Self::hline);
+    }
+    fn polygon(&mut self, points: &[(i32, i32)]) {
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            clipped_line(self, (x1, y1), points[(i + 1) % points.len()]);
+        }
+    }
+    fn fill_polygon(&mut self, points: &[(i32, i32)]) {
+        let top = points.iter().map(|p| p.1).min().unwrap_or(0).max(0);
+        let bottom = points.iter().map(|p| p.1).max().unwrap_or(0);
+        let w = self.width() as i64;
+        for y in top..bottom.min(self.height()) {
+            let mut previous = (i64::MIN, 0);
+            let mut start = None;
+            while let Some(next) = (0..points.len())
+                .filter_map(|i| crossing(points, i, y).map(|x| (x, i)))
+                .filter(|&c| c > previous)
+                .min()
+            {
+                previous = next;
+                match start.take() {
+                    None => start = Some(next.0),
+                    Some(x) => {
+                        if next.0 > x && next.0 > 0 && x < w {
+                            self.hline(x.max(0) as i32, (next.0 - 1).min(w) as i32, y)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw a shape given by the horizontal span it covers in each row from `top` to `bottom`.
+///
+/// For outlines only the parts of each span which are not covered by the rows above and below are
+/// drawn, so every pixel is drawn exactly once. The spans are computed with 64 bits, so shapes far
+/// outside of the surface do not overflow.
+fn spans<S: Surface + ?Sized>(
+    surface: &mut S,
+    top: i64,
+    bottom: i64,
+    fill: bool,
+    span: impl Fn(i32) -> Option<(i64, i64)>,
+    mut run: impl FnMut(&mut S, i32, i32, i32),
+) {
+    let w = surface.width() as i64;
+    for y in top.max(0)..=bottom.min(surface.height() as i64 - 1) {
+        let y = y as i32;
+        let Some((l, r)) = span(y) else {
+            continue;
+        };
+        if r < 0 || l >= w {
+            continue;
+        }
+        let mut clipped = |s: &mut S, l: i64, r: i64| {
+            if r >= 0 && l < w {
+                run(s, l.max(0) as i32, r.min(w - 1) as i32, y)
+            }
+        };
+        if fill {
+            clipped(surface, l, r);
+            continue;
+        }
+        match (span(y - 1), span(y + 1)) {
+            (Some((la, ra)), Some((lb, rb))) => {
+                let inner_l = (la.min(lb) - 1).max(l);
+                let inner_r = (ra.max(rb) + 1).min(r);
+                if inner_l + 1 >= inner_r {
+                    clipped(surface, l, r);
+                } else {
+                    clipped(surface, l, inner_l);
+                    clipped(surface, inner_r, r);
+                }
+            }
+            _ => clipped(surface, l, r),
+        }
+    }
+}
+
+/// Draw a line, stepping only over the part of its major axis which is inside the surface.
+///
+/// Far away end points neither take long to draw nor overflow.
+fn clipped_line<S: Surface + ?Sized>(surface: &mut S, from: (i32, i32), to: (i32, i32)) {
+    let steep = (to.1 as i64 - from.1 as i64).abs() > (to.0 as i64 - from.0 as i64).abs();
+    let swap = |(x, y): (i32, i32)| if steep { (y, x) } else { (x, y) };
+    let (mut a, mut b) = (swap(from), swap(to));
+    if a.0 > b.0 {
+        (a, b) = (b, a);
+    }
+    let (major, minor) = swap((surface.width(), surface.height()));
+    let d = b.0 as i128 - a.0 as i128;
+    let n = b.1 as i128 - a.1 as i128;
+    for i in a.0.max(0)..=b.0.min(major - 1) {
+        let j = if d == 0 {
+            a.1 as i128
+        } else {
+            a.1 as i128 + (2 * (i as i128 - a.0 as i128) * n + d).div_euclid(2 * d)
+        };
+        if (0..minor as i128).contains(&j) {
+            let (x, y) = swap((i, j as i32));
+            surface.pixel(x, y);
+        }
+    }
+}
+
+fn ellipse_span(x: i32, y: i32, rx: i32, ry: i32) -> impl Fn(i32) -> Option<(i64, i64)> {
+    let (x, rx, ry) = (x as i64, rx as i64, ry as i64);
+    move |j| {
+        let dy = (j as i64 - y as i64).abs();
+        if rx < 0 || dy > ry {
+            return None;
+        }
+        if rx == 0 || ry == 0 {
+            return Some((x - rx, x + rx));
+        }
+        let (rx, ry, dy) = (rx as i128, ry as i128, dy as i128);
+        let k = rx * rx * ry * ry + rx * ry * (rx + ry) / 2;
+        let w = ((k - dy * dy * rx * rx) / (ry * ry)).isqrt().min(rx) as i64;
+        Some((x - w, x + w))
+    }
+}
+
+fn rounded_span(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    r: i32,
+) -> impl Fn(i32) -> Option<(i64, i64)> {
+    let (x, y, width, height) = (x as i64, y as i64, width as i64, height as i64);
+    let r = (r as i64).min((width - 1) / 2).min((height - 1) / 2).max(0);
+    move |j| {
+        let j = j as i64;
+        if width <= 0 || !(y..y + height).contains(&j) {
+            return None;
+        }
+        let dy = (y + r - j).max(j - (y + height - 1 - r)).max(0);
+        let w = (r * r + r - dy * dy).isqrt().min(r);
+        Some((x + r - w, x + width - 1 - r + w))
+    }
+}
+
+fn convex_span(points: &[(i32, i32)]) -> impl Fn(i32) -> Option<(i64, i64)> + '_ {
+    move |y| {
+        let mut span: Option<(i64, i64)> = None;
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            let (x2, y2) = points[(i + 1) % points.len()];
+            if !(y1.min(y2)..=y1.max(y2)).contains(&y) {
+                continue;
+            }
+            let (l, r) = if y1 == y2 {
+                (x1.min(x2) as i64, x1.max(x2) as i64)
+            } else {
+                let n = (y as i128 - y1 as i128) * (x2 as i128 - x1 as i128);
+                let d = y2 as i128 - y1 as i128;
+                let x = x1 as i128 + (2 * n + d).div_euclid(2 * d);
+                (x as i64, x as i64)
+            };
+            span = Some(span.map_or((l, r), |(a, b)| (a.min(l), b.max(r))));
+        }
+        span
+    }
+}
+
+/// Returns the first pixel whose center is right of the point where the edge starting at point
+/// `i` crosses the center of row `y`.
+fn crossing(points: &[(i32, i32)], i: usize, y: i32) -> Option<i64> {
+    let (mut x1, mut y1) = points[i];
+    let (mut x2, mut y2) = points[(i + 1) % points.len()];
+    if y1 > y2 {
+        (x1, y1, x2, y2) = (x2, y2, x1, y1);
+    }
+    if !(y1..y2).contains(&y) {
+        return None;
+    }
+    let d = y2 as i128 - y1 as i128;
+    let n =
+        (2 * x1 as i128 - 1) * d + (2 * (y as i128 - y1 as i128) + 1) * (x2 as i128 - x1 as i128);
+    Some(-((-n).div_euclid(2 * d)) as i64)
+}
+
+/// The part of a circle between two angles
+#[derive(Clone, Copy)]
+struct Sector {
+    start: (i64, i64),
+    end: (i64, i64),
+    sweep: i32,
+}
+
+impl Sector {
+    fn new(start: i32, end: i32) -> Self {
+        let sweep = end as i64 - start as i64;
+        let sweep = if sweep >= 360 {
+            360
+        } else {
+            sweep.rem_euclid(360) as i32
+        };
+        Self {
+            start: direction(start),
+            end: direction(end),
+            sweep,
+        }
+    }
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let p = (x, y);
+        let cross = |a: (i64, i64), b: (i64, i64)| a.0 * b.1 - a.1 * b.0;
+        if self.sweep >= 360 {
+            true
+        } else if self.sweep <= 180 {
+            cross(self.start, p) >= 0 && cross(p, self.end) >= 0
+        } else {
+            !(cross(self.end, p) > 0 && cross(p, self.start) > 0)
+        }
+    }
+}
+
+/// Returns the direction of the angle in degrees scaled by 16384
+fn direction(angle: i32) -> (i64, i64) {
+    (sin(angle as i64 + 90), sin(angle as i64))
+}
+
+fn sin(angle: i64) -> i64 {
+    let a = angle.rem_euclid(360) as usize;
+    match a {
+        0..=90 => SIN[a],
+        91..=180 => SIN[180 - a],
+        181..=270 => -SIN[a - 180],
+        _ => -SIN[360 - a],
+    }
+}
+
+const SIN: [i64; 91] = [
+    0, 286, 572, 857, 1143, 1428, 1713, 1997, 2280, 2563, 2845, 3126, 3406, 3686, 3964, 4240, 4516,
+    4790, 5063, 5334, 5604, 5872, 6138, 6402, 6664, 6924, 7182, 7438, 7692, 7943, 8192, 8438, 8682,
+    8923, 9162, 9397, 9630, 9860, 10087, 10311, 10531, 10749, 10963, 11174, 11381, 11585, 11786,
+    11982, 12176, 12365, 12551, 12733, 12911, 13085, 13255, 13421, 13583, 13741, 13894, 14044,
+    14189, 14330, 14466, 14598, 14726, 14849, 14968, 15082, 15191, 15296, 15396, 15491, 15582,
+    15668, 15749, 15826, 15897, 15964, 16026, 16083, 16135, 16182, 16225, 16262, 16294, 16322,
+    16344, 16362, 16374, 16382, 16384,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Framebuffer, Paint, Rows};
+
+    type Frame = Framebuffer<8, 8, 8, Rows>;
+
+    #[test]
+    fn rectangles() {
+        let mut frame = Frame::new();
+        frame.rect(-2, 1, 6, 3);
+        assert_eq!(frame.as_bytes(), &[0, 0xf0, 0x10, 0xf0, 0, 0, 0, 0]);
+        frame.clear();
+        frame.fill_rect(6, 6, 100, 100);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0, 0, 0, 3, 3]);
+        frame.clear();
+        frame.fill_polygon(&[(6, 6), (100, 6), (100, 100), (6, 100)]);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0, 0, 0, 3, 3]);
+    }
+
+    #[test]
+    fn polygons() {
+        let mut frame = Frame::new();
+        frame.polygon(&[(1, 1), (5, 1), (5, 3), (1, 3)]);
+        assert_eq!(frame.as_bytes(), &[0, 0x7c, 0x44, 0x7c, 0, 0, 0, 0]);
+        frame.clear();
+        frame.polygon(&[(0, 0), (7, 0), (7, 2_000_000_000)]);
+        assert_eq!(
+            frame.as_bytes(),
+            &[0xff, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81]
+        );
+        frame.clear();
+        frame.polygon(&[(i32::MIN, 4), (i32::MAX, 4), (3, 3)]);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0xff, 0xff, 0, 0, 0]);
+        frame.clear();
+        frame.polygon(&[(-100, -100), (-50, -100), (-50, -50)]);
+        assert_eq!(frame.as_bytes(), &[0; 8]);
+    }
+
+    #[test]
+    fn far_away() {
+        let mut frame = Frame::new();
+        frame.fill_polygon(&[(i32::MIN, 0), (i32::MAX, 0), (0, 50)]);
+        assert_eq!(frame.as_bytes(), &[0xff; 8]);
+        frame.clear();
+        frame.fill_triangle(i32::MIN, i32::MIN, i32::MAX, 4, i32::MIN, i32::MAX);
+        frame.triangle(i32::MIN, i32::MIN, i32::MAX, 4, i32::MIN, i32::MAX);
+        assert_eq!(frame.as_bytes(), &[0xff; 8]);
+        frame.clear();
+        frame.circle(4, 100_004, 100_000);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0, 0xff, 0, 0, 0]);
+        frame.fill_circle(4, 60_004, 60_000);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        frame.clear();
+        frame.rounded_rect(-100_000, -100_000, 200_000, 200_000, 100_000);
+        assert_eq!(frame.as_bytes(), &[0; 8]);
+        frame.fill_rounded_rect(-100_000, -100_000, 200_000, 200_000, 100_000);
+        assert_eq!(frame.as_bytes(), &[0xff; 8]);
+        frame.clear();
+        frame.rect(i32::MAX - 5, 0, 10, 10);
+        frame.ellipse(0, i32::MIN + 5, 10, 10);
+        frame.fill_ellipse(i32::MAX, i32::MAX, i32::MAX, i32::MAX);
+        frame.arc(i32::MIN, 3, i32::MAX, i32::MIN, i32::MAX);
+        assert_eq!(frame.as_bytes(), &[0; 8]);
+        frame.rect(2, 2, i32::MAX, i32::MAX);
+        assert_eq!(
+            frame.as_bytes(),
+            &[0, 0, 0x3f, 0x20, 0x20, 0x20, 0x20, 0x20]
+        );
+        frame.clear();
+        frame.fill_rect(i32::MIN, 6, i32::MAX, i32::MAX);
+        frame.fill_arc(3, 3, i32::MAX, 0, 90);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f]);
+    }
+
+    #[test]
+    fn circles() {
+        let mut frame = Frame::new();
+        frame.circle(3, 3, 3);
+        let outline = *frame.as_bytes();
+        assert_eq!(outline, [0x38, 0x44, 0x82, 0x82, 0x82, 0x44, 0x38, 0]);
+        Paint::toggle(&mut frame).fill_circle(3, 3, 3);
+        Paint::toggle(&mut frame).circle(3, 3, 3);
+        assert_eq!(
+            frame.as_bytes(),
+            &[0x38, 0x7c, 0xfe, 0xfe, 0xfe, 0x7c, 0x38, 0]
+        );
+        Paint::unset(&mut frame).fill_circle(3, 3, 3);
+        frame.fill_arc(3, 3, 3, 0, 90);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0x1e, 0x1e, 0x1c, 0x18, 0]);
+        frame.circle(-1000, 4, 10);
+        frame.fill_circle(4, -1000, 10);
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0x1e, 0x1e, 0x1c, 0x18, 0]);
+    }
+}
@@ -32,6 +32,15 @@ impl<S: Surface> Surface for Rotate<S> {
     fn pixel(&mut self, x: i32, y: i32) {
         self.0.pixel(self.0.width() - 1 - y, x)
     }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.0.unset(self.0.width() - 1 - y, x)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.0.toggle(self.0.width() - 1 - y, x)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.0.get(self.0.width() - 1 - y, x)
+    }
 }
 impl<S> Deref for Rotate<S> {
     type Target = S;
@@ -45,12 +54,493 @@ impl<S> DerefMut for Rotate<S> {
     }
 }
 
+/// Mirror a surface horizontally
+#[derive(Debug)]
+pub struct FlipX<S>(S);
+impl<S> FlipX<S> {
+    /// Swap left and right
+    pub fn new(surface: S) -> FlipX<S> {
+        FlipX(surface)
+    }
+}
+impl<S: Surface> Surface for FlipX<S> {
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+    fn height(&self) -> i32 {
+        self.0.height()
+    }
+    fn width(&self) -> i32 {
+        self.0.width()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.0.pixel(self.0.width() - 1 - x, y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.0.unset(self.0.width() - 1 - x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.0.toggle(self.0.width() - 1 - x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.0.get(self.0.width() - 1 - x, y)
+    }
+}
+impl<S> Deref for FlipX<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<S> DerefMut for FlipX<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Mirror a surface vertically
+#[derive(Debug)]
+pub struct FlipY<S>(S);
+impl<S> FlipY<S> {
+    /// Swap top and bottom
+    pub fn new(surface: S) -> FlipY<S> {
+        FlipY(surface)
+    }
+}
+impl<S: Surface> Surface for FlipY<S> {
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+    fn height(&self) -> i32 {
+        self.0.height()
+    }
+    fn width(&self) -> i32 {
+        self.0.width()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.0.pixel(x, self.0.height() - 1 - y)
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.0.unset(x, self.0.height() - 1 - y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.0.toggle(x, self.0.height() - 1 - y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.0.get(x, self.0.height() - 1 - y)
+    }
+}
+impl<S> Deref for FlipY<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<S> DerefMut for FlipY<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Scale a surface by an integer factor
+///
+/// Every pixel becomes a square of `N` by `N` pixels on the underlying surface.
+///
+/// ```
+/// use premium_pixel::{Framebuffer, Premium, Scale};
+///
+/// let mut frame = Framebuffer::<128, 64, 1024>::new();
+/// Scale::<3, _>::new(&mut frame).premium(0, 12, "42");
+/// ```
+#[derive(Debug)]
+pub struct Scale<const N: i32, S>(S);
+impl<const N: i32, S> Scale<N, S> {
+    /// Scale by `N`
+    pub fn new(surface: S) -> Scale<N, S> {
+        const { assert!(N > 0) };
+        Scale(surface)
+    }
+}
+impl<const N: i32, S: Surface> Surface for Scale<N, S> {
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+    fn height(&self) -> i32 {
+        self.0.height() / N
+    }
+    fn width(&self) -> i32 {
+        self.0.width() / N
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        for j in 0..N {
+            for i in 0..N {
+                self.0.pixel(x * N + i, y * N + j)
+            }
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        for j in 0..N {
+            for i in 0..N {
+                self.0.unset(x * N + i, y * N + j)
+            }
+        }
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        for j in 0..N {
+            for i in 0..N {
+                self.0.toggle(x * N + i, y * N + j)
+            }
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.0.get(x * N, y * N)
+    }
+}
+impl<const N: i32, S> Deref for Scale<N, S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<const N: i32, S> DerefMut for Scale<N, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A rectangle with the top left corner at `(x, y)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// Left edge
+    pub x: i32,
+    /// Top edge
+    pub y: i32,
+    /// Width in pixels
+    pub width: i32,
+    /// Height in pixels
+    pub height: i32,
+}
+impl Rect {
+    /// Create a new rectangle
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    /// Check if the rectangle contains a pixel
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && (x as i64) < self.right() && y >= self.y && (y as i64) < self.bottom()
+    }
+    /// Returns the part of the rectangle which is also covered by the other rectangle.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let width = self.right().min(other.right()) - x as i64;
+        let height = self.bottom().min(other.bottom()) - y as i64;
+        Rect::new(x, y, clamp(width), clamp(height))
+    }
+    /// Returns the smallest rectangle which covers both rectangles.
+    /// Empty rectangles are ignored.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.width <= 0 || other.height <= 0 {
+            return *self;
+        }
+        if self.width <= 0 || self.height <= 0 {
+            return *other;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let width = self.right().max(other.right()) - x as i64;
+        let height = self.bottom().max(other.bottom()) - y as i64;
+        Rect::new(x, y, clamp(width), clamp(height))
+    }
+    /// The right edge, which can be outside of the range of `i32`
+    const fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+    /// The bottom edge, which can be outside of the range of `i32`
+    const fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+}
+
+/// Clamp a size to the range from 0 to `i32::MAX`
+fn clamp(size: i64) -> i32 {
+    size.clamp(0, i32::MAX as i64) as i32
+}
+
+/// Move the origin of a surface
+///
+/// The width and height are reduced by the offset, so the surface covers everything from the new
+/// origin to the bottom right corner.
+#[derive(Debug)]
+pub struct Translate<S> {
+    surface: S,
+    x: i32,
+    y: i32,
+}
+impl<S> Translate<S> {
+    /// Move the origin to the given position
+    pub fn new(surface: S, x: i32, y: i32) -> Translate<S> {
+        Translate { surface, x, y }
+    }
+}
+impl<S: Surface> Surface for Translate<S> {
+    fn clear(&mut self) {
+        self.surface.clear()
+    }
+    fn height(&self) -> i32 {
+        self.surface.height().saturating_sub(self.y)
+    }
+    fn width(&self) -> i32 {
+        self.surface.width().saturating_sub(self.x)
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        self.surface
+            .pixel(x.saturating_add(self.x), y.saturating_add(self.y))
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.surface
+            .unset(x.saturating_add(self.x), y.saturating_add(self.y))
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.surface
+            .toggle(x.saturating_add(self.x), y.saturating_add(self.y))
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.surface
+            .get(x.saturating_add(self.x), y.saturating_add(self.y))
+    }
+}
+impl<S> Deref for Translate<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.surface
+    }
+}
+impl<S> DerefMut for Translate<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.surface
+    }
+}
+
+/// Only draw inside of a rectangle
+///
+/// Pixels outside of the rectangle are dropped and clearing only clears the rectangle.
+#[derive(Debug)]
+pub struct Clip<S> {
+    surface: S,
+    rect: Rect,
+}
+impl<S> Clip<S> {
+    /// Clip to the given rectangle
+    pub fn new(surface: S, rect: Rect) -> Clip<S> {
+        Clip { surface, rect }
+    }
+}
+impl<S: Surface> Surface for Clip<S> {
+    fn clear(&mut self) {
+        clear_rect(&mut self.surface, self.rect)
+    }
+    fn height(&self) -> i32 {
+        self.surface.height()
+    }
+    fn width(&self) -> i32 {
+        self.surface.width()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        if self.rect.contains(x, y) {
+            self.surface.pixel(x, y)
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        if self.rect.contains(x, y) {
+            self.surface.unset(x, y)
+        }
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        if self.rect.contains(x, y) {
+            self.surface.toggle(x, y)
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.rect.contains(x, y) && self.surface.get(x, y)
+    }
+}
+impl<S> Deref for Clip<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.surface
+    }
+}
+impl<S> DerefMut for Clip<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.surface
+    }
+}
+
+/// A part of a surface with its own coordinates
+///
+/// The top left corner of the rectangle becomes the origin and the size of the rectangle becomes
+/// the size of the surface. Pixels outside of the rectangle are dropped and clearing only clears
+/// the rectangle.
+#[derive(Debug)]
+pub struct Viewport<S> {
+    surface: S,
+    rect: Rect,
+}
+impl<S> Viewport<S> {
+    /// Create a viewport for the given rectangle
+    pub fn new(surface: S, rect: Rect) -> Viewport<S> {
+        Viewport { surface, rect }
+    }
+}
+impl<S: Surface> Surface for Viewport<S> {
+    fn clear(&mut self) {
+        clear_rect(&mut self.surface, self.rect)
+    }
+    fn height(&self) -> i32 {
+        self.rect.height
+    }
+    fn width(&self) -> i32 {
+        self.rect.width
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        if (0..self.rect.width).contains(&x) && (0..self.rect.height).contains(&y) {
+            self.surface
+                .pixel(x.saturating_add(self.rect.x), y.saturating_add(self.rect.y))
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        if (0..self.rect.width).contains(&x) && (0..self.rect.height).contains(&y) {
+            self.surface
+                .unset(x.saturating_add(self.rect.x), y.saturating_add(self.rect.y))
+        }
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        if (0..self.rect.width).contains(&x) && (0..self.rect.height).contains(&y) {
+            self.surface
+                .toggle(x.saturating_add(self.rect.x), y.saturating_add(self.rect.y))
+        }
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        (0..self.rect.width).contains(&x)
+            && (0..self.rect.height).contains(&y)
+            && self
+                .surface
+                .get(x.saturating_add(self.rect.x), y.saturating_add(self.rect.y))
+    }
+}
+impl<S> Deref for Viewport<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.surface
+    }
+}
+impl<S> DerefMut for Viewport<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.surface
+    }
+}
+
+fn clear_rect<S: Surface>(surface: &mut S, rect: Rect) {
+    let rect = rect.intersection(&Rect::new(0, 0, surface.width(), surface.height()));
+    for y in rect.y..rect.y + rect.height {
+        for x in rect.x..rect.x + rect.width {
+            surface.unset(x, y);
+        }
+    }
+}
+
+/// How [`Paint`] draws a pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Fill the pixel
+    Set,
+    /// Clear the pixel
+    Unset,
+    /// Invert the pixel
+    Toggle,
+}
+
+/// Draw on a surface with a different mode
+///
+/// Everything drawn on a `Paint` surface uses the mode for filling pixels, so the same text or
+/// line can be drawn, erased or inverted.
+#[derive(Debug)]
+pub struct Paint<S> {
+    surface: S,
+    mode: Mode,
+}
+impl<S> Paint<S> {
+    /// Draw with the given mode
+    pub fn new(surface: S, mode: Mode) -> Paint<S> {
+        Paint { surface, mode }
+    }
+    /// Clear pixels instead of filling them
+    pub fn unset(surface: S) -> Paint<S> {
+        Paint::new(surface, Mode::Unset)
+    }
+    /// Invert pixels instead of filling them
+    pub fn toggle(surface: S) -> Paint<S> {
+        Paint::new(surface, Mode::Toggle)
+    }
+}
+impl<S: Surface> Surface for Paint<S> {
+    fn clear(&mut self) {
+        self.surface.clear()
+    }
+    fn height(&self) -> i32 {
+        self.surface.height()
+    }
+    fn width(&self) -> i32 {
+        self.surface.width()
+    }
+    fn pixel(&mut self, x: i32, y: i32) {
+        match self.mode {
+            Mode::Set => self.surface.pixel(x, y),
+            Mode::Unset => self.surface.unset(x, y),
+            Mode::Toggle => self.surface.toggle(x, y),
+        }
+    }
+    fn unset(&mut self, x: i32, y: i32) {
+        self.surface.unset(x, y)
+    }
+    fn toggle(&mut self, x: i32, y: i32) {
+        self.surface.toggle(x, y)
+    }
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.surface.get(x, y)
+    }
+}
+impl<S> Deref for Paint<S> {
+    type Target = S;
+    fn deref(&self) -> &Self::Target {
+        &self.surface
+    }
+}
+impl<S> DerefMut for Paint<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.surface
+    }
+}
+
 /// This struct implements the Surface trait and can be used to measure the width of text without
 /// drawing anything
+///
+/// Fonts can be measured directly with [`Font::metrics`](crate::Font::metrics), which is faster
+/// and also returns the height of the text.
 pub struct Measure;
 impl Surface for Measure {
     fn clear(&mut self) {}
     fn pixel(&mut self, _x: i32, _y: i32) {}
+    fn unset(&mut self, _x: i32, _y: i32) {}
+    fn get(&self, _x: i32, _y: i32) -> bool {
+        false
+    }
     fn height(&self) -> i32 {
         1000
     }
@@ -58,3 +548,116 @@ impl Surface for Measure {
         1000
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Framebuffer, Rows, Shapes};
+
+    type Frame = Framebuffer<8, 8, 8, Rows>;
+
+    #[test]
+    fn flip() {
+        let mut frame = Frame::new();
+        let mut flip = FlipX::new(&mut frame);
+        flip.pixel(0, 1);
+        flip.pixel(2, 2);
+        flip.unset(2, 2);
+        assert!(flip.get(0, 1) && !flip.get(7, 1));
+        assert_eq!(frame.as_bytes(), &[0, 0x01, 0, 0, 0, 0, 0, 0]);
+        let mut flip = FlipY::new(&mut frame);
+        flip.pixel(1, 0);
+        flip.toggle(6, 6);
+        assert!(flip.get(1, 0) && flip.get(6, 6) && !flip.get(1, 7));
+        assert_eq!(frame.as_bytes(), &[0, 0x03, 0, 0, 0, 0, 0, 0x40]);
+    }
+
+    #[test]
+    fn scale() {
+        let mut frame = Frame::new();
+        let mut scale = Scale::<3, _>::new(&mut frame);
+        assert_eq!((scale.width(), scale.height()), (2, 2));
+        scale.pixel(1, 0);
+        assert_eq!(frame.as_bytes(), &[0x1c, 0x1c, 0x1c, 0, 0, 0, 0, 0]);
+        let mut scale = Scale::<3, _>::new(&mut frame);
+        scale.toggle(0, 0);
+        scale.toggle(1, 1);
+        assert!(scale.get(0, 0) && scale.get(1, 1) && !scale.get(0, 1));
+        assert_eq!(
+            frame.as_bytes(),
+            &[0xfc, 0xfc, 0xfc, 0x1c, 0x1c, 0x1c, 0, 0]
+        );
+        let mut scale = Scale::<3, _>::new(&mut frame);
+        scale.unset(1, 0);
+        assert!(!scale.get(1, 0));
+        assert_eq!(
+            frame.as_bytes(),
+            &[0xe0, 0xe0, 0xe0, 0x1c, 0x1c, 0x1c, 0, 0]
+        );
+    }
+
+    #[test]
+    fn rects() {
+        let rect = Rect::new(0, 0, 4, 4);
+        assert_eq!(
+            rect.intersection(&Rect::new(2, -1, 10, 3)),
+            Rect::new(2, 0, 2, 2)
+        );
+        assert_eq!(
+            rect.intersection(&Rect::new(5, 5, 2, 2)),
+            Rect::new(5, 5, 0, 0)
+        );
+        assert!(rect.contains(3, 0) && !rect.contains(4, 0) && !rect.contains(0, -1));
+        let far = Rect::new(i32::MAX - 2, i32::MIN, 10, i32::MAX);
+        assert_eq!(far.intersection(&far), far);
+        assert_eq!(far.intersection(&rect), Rect::new(i32::MAX - 2, 0, 0, 0));
+        assert_eq!(far.union(&rect), Rect::new(0, i32::MIN, i32::MAX, i32::MAX));
+        assert!(far.contains(i32::MAX, -2) && !Rect::new(-1, 0, 1, 1).contains(i32::MAX, 0));
+    }
+
+    #[test]
+    fn translate() {
+        let mut frame = Frame::new();
+        let mut translate = Translate::new(&mut frame, 2, 3);
+        assert_eq!((translate.width(), translate.height()), (6, 5));
+        translate.pixel(0, 0);
+        translate.pixel(5, 4);
+        assert!(translate.get(0, 0) && !translate.get(1, 0));
+        assert_eq!(frame.as_bytes(), &[0, 0, 0, 0x20, 0, 0, 0, 0x01]);
+    }
+
+    #[test]
+    fn clip() {
+        let mut frame = Frame::new();
+        frame.pixel(0, 0);
+        let mut clip = Clip::new(&mut frame, Rect::new(2, 2, 4, 3));
+        assert_eq!((clip.width(), clip.height()), (8, 8));
+        clip.fill_rect(0, 0, 8, 8);
+        clip.pixel(1, 2);
+        clip.toggle(6, 4);
+        assert!(clip.get(2, 2) && !clip.get(0, 0));
+        assert_eq!(frame.as_bytes(), &[0x80, 0, 0x3c, 0x3c, 0x3c, 0, 0, 0]);
+        Clip::new(&mut frame, Rect::new(2, 2, 4, 3)).clear();
+        assert_eq!(frame.as_bytes(), &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn viewport() {
+        let mut frame = Frame::new();
+        frame.pixel(0, 0);
+        let mut viewport = Viewport::new(&mut frame, Rect::new(5, 6, 4, 4));
+        assert_eq!((viewport.width(), viewport.height()), (4, 4));
+        viewport.fill_rect(-1, -1, 6, 6);
+        viewport.pixel(-1, 1);
+        viewport.pixel(0, 4);
+        assert!(viewport.get(0, 0) && viewport.get(2, 1) && !viewport.get(3, 3));
+        assert_eq!(frame.as_bytes(), &[0x80, 0, 0, 0, 0, 0, 0x07, 0x07]);
+        Viewport::new(&mut frame, Rect::new(5, 6, 4, 4)).clear();
+        assert_eq!(frame.as_bytes(), &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+        let mut viewport = Viewport::new(&mut frame, Rect::new(i32::MAX - 2, 0, 10, 10));
+        viewport.fill_rect(0, 0, 10, 10);
+        viewport.clear();
+        Translate::new(&mut frame, i32::MIN, i32::MAX).fill_rect(0, 0, 10, 10);
+        assert_eq!(frame.as_bytes(), &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Align, Font, Rect, Surface};
+
+/// Iterator over the lines of a text wrapped to a maximum width
+///
+/// Lines are broken at `\n` and between words. Words which are wider than a whole line are broken
+/// between characters. Created by [`Font::lines`].
+#[derive(Debug, Clone)]
+pub struct Lines<'a, F> {
+    font: &'a F,
+    width: i32,
+    paragraphs: core::str::Lines<'a>,
+    rest: Option<&'a str>,
+}
+
+impl<'a, F: Font> Lines<'a, F> {
+    pub(crate) fn new(font: &'a F, text: &'a str, width: i32) -> Self {
+        Self {
+            font,
+            width,
+            paragraphs: text.lines(),
+            rest: None,
+        }
+    }
+}
+
+impl<'a, F: Font> Iterator for Lines<'a, F> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = match self.rest.take() {
+            Some(text) => text,
+            None => self.paragraphs.next()?,
+        };
+        let fits = |line: &str| self.font.advance(line.trim_end_matches(' ')) <= self.width;
+        let mut end = text.len();
+        if !fits(text) {
+            end = text
+                .match_indices(' ')
+                .map(|(i, _)| i)
+                .take_while(|&i| fits(&text[..i]))
+                .last()
+                .filter(|&i| !text[..i].trim_end_matches(' ').is_empty())
+                .or_else(|| {
+                    text.char_indices()
+                        .map(|(i, c)| i + c.len_utf8())
+                        .take_while(|&i| fits(&text[..i]))
+                        .last()
+                })
+                .unwrap_or_else(|| text.chars().next().map_or(0, char::len_utf8));
+        }
+        let rest = text[end..].trim_start_matches(' ');
+        if !rest.is_empty() {
+            self.rest = Some(rest);
+        }
+        Some(text[..end].trim_end_matches(' '))
+    }
+}
+
+/// Longest line in bytes which is measured and drawn together with the ellipsis
+///
+/// Longer lines are followed by the ellipsis after the spacing of the font.
+const JOINED: usize = 128;
+
+/// Write the line followed by the ellipsis into the buffer.
+/// Returns `None` if they do not fit.
+fn join<'b>(buffer: &'b mut [u8; JOINED], line: &str, ellipsis: &str) -> Option<&'b str> {
+    let joined = buffer.get_mut(..line.len() + ellipsis.len())?;
+    let (start, end) = joined.split_at_mut(line.len());
+    start.copy_from_slice(line.as_bytes());
+    end.copy_from_slice(ellipsis.as_bytes());
+    core::str::from_utf8(joined).ok()
+}
+
+/// Returns the width of the line followed by the ellipsis.
+///
+/// Both are measured together, so conditional spacing and ligatures are taken into account.
+fn joined_advance<F: Font>(font: &F, line: &str, ellipsis: &str) -> i32 {
+    let mut buffer = [0; JOINED];
+    match join(&mut buffer, line, ellipsis) {
+        Some(joined) => font.advance(joined),
+        None => font.advance(line) + font.spacing() + font.advance(ellipsis),
+    }
+}
+
+/// Write the text into a box, see [`Font::draw_wrapped`].
+pub(crate) fn draw_wrapped<S: Surface + ?Sized, F: Font>(
+    font: &F,
+    surface: &mut S,
+    rect: Rect,
+    text: &str,
+    align: Align,
+    ellipsis: Option<&str>,
+) -> bool {
+    let height = rect.height - font.ascent() - font.descent();
+    if height < 0 {
+        return !text.is_empty();
+    }
+    let visible = height / font.line_height() + 1;
+    let mut lines = font.lines(text, rect.width).peekable();
+    let mut y = rect.y + font.ascent();
+    let mut buffer = [0; JOINED];
+    for i in 0..visible {
+        let Some(mut line) = lines.next() else {
+            return false;
+        };
+        let mut suffix = None;
+        if let Some(ellipsis) = ellipsis
+            && i + 1 == visible
+            && lines.peek().is_some()
+        {
+            while !line.is_empty() && joined_advance(font, line, ellipsis) > rect.width {
+                let end = line.char_indices().last().map_or(0, |(i, _)| i);
+                line = line[..end].trim_end_matches(' ');
+            }
+            suffix = Some(ellipsis);
+        }
+        let (text, suffix) = match suffix {
+            Some(s) if line.is_empty() => (s, None),
+            Some(s) => match join(&mut buffer, line, s) {
+                Some(joined) => (joined, None),
+                None => (line, Some(s)),
+            },
+            None => (line, None),
+        };
+        let total = match suffix {
+            Some(s) => joined_advance(font, text, s),
+            None => font.advance(text),
+        };
+        let x = match align {
+            Align::Left => rect.x,
+            Align::Center => rect.x + (rect.width - total) / 2,
+            Align::Right => rect.x + rect.width - total,
+        };
+        font.draw(surface, x, y, text);
+        if let Some(s) = suffix {
+            font.draw(surface, x + total - font.advance(s), y, s);
+        }
+        y += font.line_height();
+    }
+    lines.next().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Align, AwakeningFont, Font, Framebuffer, Measure, PremiumFont, Rect};
+
+    #[test]
+    fn lines() {
+        let font = PremiumFont;
+        let mut lines = font.lines("Hello World\n\nThis is  a test", 40);
+        assert_eq!(lines.next(), Some("Hello"));
+        assert_eq!(lines.next(), Some("World"));
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(lines.next(), Some("This is"));
+        assert_eq!(lines.next(), Some("a test"));
+        assert_eq!(lines.next(), None);
+        let mut lines = font.lines("Premiumpixel", 30);
+        assert_eq!(lines.next(), Some("Pre"));
+        assert_eq!(lines.next(), Some("miu"));
+        assert_eq!(lines.next(), Some("mpix"));
+        assert_eq!(lines.next(), Some("el"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn overflow() {
+        let font = AwakeningFont;
+        let rect = Rect::new(0, 0, 40, 21);
+        let text = "The quick brown fox jumps over the lazy dog";
+        assert!(font.draw_wrapped(&mut Measure, rect, text, Align::Left, Some("…")));
+        assert!(!font.draw_wrapped(&mut Measure, rect, "The quick", Align::Center, None));
+        assert!(font.draw_wrapped(&mut Measure, Rect::new(0, 0, 40, 5), "a", Align::Left, None));
+    }
+
+    #[test]
+    fn ellipsis() {
+        let font = AwakeningFont;
+        let mut wrapped = Framebuffer::<32, 16, 64>::new();
+        let mut expected = Framebuffer::<32, 16, 64>::new();
+        let rect = Rect::new(0, 0, font.advance("The…"), 10);
+        assert!(font.draw_wrapped(&mut wrapped, rect, "The quick", Align::Right, Some("…")));
+        font.draw(&mut expected, 0, 8, "The…");
+        assert_eq!(wrapped.as_bytes(), expected.as_bytes());
+    }
+}